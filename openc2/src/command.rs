@@ -1,9 +1,11 @@
+use std::{fmt, str::FromStr};
+
 use crate::{
     Body, Check, CommandId, Content, DateTime, Duration, Error, Extensions, IsEmpty, Nsid,
     ResponseType, Target,
 };
 use serde::{Deserialize, Serialize};
-use serde_with::skip_serializing_none;
+use serde_with::{DeserializeFromStr, SerializeDisplay, skip_serializing_none};
 
 /// An OpenC2 command communicates an action to be performed on a target.
 #[skip_serializing_none]
@@ -32,6 +34,11 @@ impl<V> Command<V> {
             command_id: None,
         }
     }
+
+    /// Returns the `(action, target)` pair, for use with a `match` when dispatching a command.
+    pub fn as_action_target(&self) -> (Action, &Target<V>) {
+        (self.action.clone(), &self.target)
+    }
 }
 
 mod command_as_content {
@@ -70,19 +77,8 @@ impl<V> TryFrom<Body<Content<V>>> for Command<V> {
 
 /// The task or activity to be performed.
 #[derive(
-    Debug,
-    Serialize,
-    Deserialize,
-    PartialEq,
-    Eq,
-    Hash,
-    Clone,
-    Copy,
-    strum::EnumString,
-    strum::Display,
+    Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, SerializeDisplay, DeserializeFromStr,
 )]
-#[strum(serialize_all = "snake_case")]
-#[serde(rename_all = "snake_case")]
 #[non_exhaustive]
 pub enum Action {
     /// Systematic examination of some aspect of the entity or its environment.
@@ -111,6 +107,69 @@ pub enum Action {
     Copy,
     Investigate,
     Remediate,
+    /// An action this crate doesn't have a named variant for, e.g. a profile-defined action.
+    /// Preserves the raw string so it can be round-tripped without data loss.
+    Unknown(String),
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::Scan => write!(f, "scan"),
+            Action::Locate => write!(f, "locate"),
+            Action::Query => write!(f, "query"),
+            Action::Deny => write!(f, "deny"),
+            Action::Contain => write!(f, "contain"),
+            Action::Allow => write!(f, "allow"),
+            Action::Start => write!(f, "start"),
+            Action::Stop => write!(f, "stop"),
+            Action::Restart => write!(f, "restart"),
+            Action::Cancel => write!(f, "cancel"),
+            Action::Set => write!(f, "set"),
+            Action::Update => write!(f, "update"),
+            Action::Redirect => write!(f, "redirect"),
+            Action::Create => write!(f, "create"),
+            Action::Delete => write!(f, "delete"),
+            Action::Detonate => write!(f, "detonate"),
+            Action::Restore => write!(f, "restore"),
+            Action::Copy => write!(f, "copy"),
+            Action::Investigate => write!(f, "investigate"),
+            Action::Remediate => write!(f, "remediate"),
+            Action::Unknown(action) => write!(f, "{action}"),
+        }
+    }
+}
+
+impl FromStr for Action {
+    type Err = std::convert::Infallible;
+
+    /// Parses any of the known snake_case action names into their variant, or falls back to
+    /// [`Unknown`](Action::Unknown) with the raw string preserved - never errors.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "scan" => Action::Scan,
+            "locate" => Action::Locate,
+            "query" => Action::Query,
+            "deny" => Action::Deny,
+            "contain" => Action::Contain,
+            "allow" => Action::Allow,
+            "start" => Action::Start,
+            "stop" => Action::Stop,
+            "restart" => Action::Restart,
+            "cancel" => Action::Cancel,
+            "set" => Action::Set,
+            "update" => Action::Update,
+            "redirect" => Action::Redirect,
+            "create" => Action::Create,
+            "delete" => Action::Delete,
+            "detonate" => Action::Detonate,
+            "restore" => Action::Restore,
+            "copy" => Action::Copy,
+            "investigate" => Action::Investigate,
+            "remediate" => Action::Remediate,
+            other => Action::Unknown(other.to_string()),
+        })
+    }
 }
 
 #[skip_serializing_none]
@@ -139,6 +198,74 @@ impl Period {
 
         errors.finish()
     }
+
+    /// Derives whichever of `start_time`, `stop_time`, and `duration` is missing from the other
+    /// two, per `stop_time = start_time + duration`.
+    ///
+    /// Returns an error if fewer than two fields are set, or if all three are set but
+    /// inconsistent with that equation.
+    pub fn resolve(&self) -> Result<ResolvedPeriod, Error> {
+        match (self.start_time, self.stop_time, self.duration) {
+            (Some(start_time), Some(stop_time), Some(duration)) => {
+                if start_time.checked_add(duration) != Some(stop_time) {
+                    return Err(Error::validation(
+                        "start_time + duration must equal stop_time",
+                    )
+                    .at("duration"));
+                }
+
+                Ok(ResolvedPeriod {
+                    start_time,
+                    stop_time,
+                    duration,
+                })
+            }
+            (Some(start_time), Some(stop_time), None) => Ok(ResolvedPeriod {
+                start_time,
+                stop_time,
+                duration: stop_time.checked_duration_since(start_time).ok_or_else(|| {
+                    Error::validation("start_time must not be after stop_time").at("start_time")
+                })?,
+            }),
+            (Some(start_time), None, Some(duration)) => Ok(ResolvedPeriod {
+                start_time,
+                stop_time: start_time.checked_add(duration).ok_or_else(|| {
+                    Error::validation("start_time + duration overflows").at("duration")
+                })?,
+                duration,
+            }),
+            (None, Some(stop_time), Some(duration)) => Ok(ResolvedPeriod {
+                start_time: stop_time.checked_sub(duration).ok_or_else(|| {
+                    Error::validation("duration must not exceed stop_time").at("duration")
+                })?,
+                stop_time,
+                duration,
+            }),
+            _ => Err(Error::validation(
+                "at least two of start_time, stop_time, and duration must be specified",
+            )),
+        }
+    }
+
+    /// Whether `at` falls within this period, per [`Self::resolve`].
+    ///
+    /// Returns `false` if this period doesn't resolve, e.g. because fewer than two fields are
+    /// set.
+    pub fn contains(&self, at: DateTime) -> bool {
+        self.resolve()
+            .is_ok_and(|resolved| resolved.start_time <= at && at <= resolved.stop_time)
+    }
+
+    /// Whether this period and `other` share any instant in time, per [`Self::resolve`].
+    ///
+    /// Returns `false` if either period doesn't resolve.
+    pub fn overlaps(&self, other: &Period) -> bool {
+        let (Ok(this), Ok(other)) = (self.resolve(), other.resolve()) else {
+            return false;
+        };
+
+        this.start_time <= other.stop_time && other.start_time <= this.stop_time
+    }
 }
 
 impl IsEmpty for Period {
@@ -149,20 +276,27 @@ impl IsEmpty for Period {
 
 impl Check for Period {
     fn check(&self) -> Result<(), Error> {
-        let mut acc = Error::accumulator();
-        if self.start_time.is_some() && self.stop_time.is_some() && self.duration.is_some() {
-            acc.push(
-                Error::validation(
-                    "Only two of start_time, stop_time, and duration may be specified at once",
-                )
-                .at("duration"),
-            );
+        let set_fields = self.start_time.is_some() as u8
+            + self.stop_time.is_some() as u8
+            + self.duration.is_some() as u8;
+
+        if set_fields < 2 {
+            return Ok(());
         }
 
-        acc.finish()
+        self.resolve().map(|_| ())
     }
 }
 
+/// A [`Period`] with `start_time`, `stop_time`, and `duration` all populated and mutually
+/// consistent, as returned by [`Period::resolve`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResolvedPeriod {
+    pub start_time: DateTime,
+    pub stop_time: DateTime,
+    pub duration: Duration,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Args<V> {
@@ -206,7 +340,8 @@ impl<V> Default for Args<V> {
 
 #[cfg(test)]
 mod tests {
-    use super::Action;
+    use super::{Action, Check, Period, ResolvedPeriod};
+    use crate::{DateTime, Duration};
 
     #[test]
     fn action_display() {
@@ -217,4 +352,115 @@ mod tests {
     fn action_from_str() {
         assert_eq!("scan".parse::<Action>().unwrap(), Action::Scan);
     }
+
+    #[test]
+    fn unrecognized_action_round_trips_through_unknown() {
+        let action: Action = "fortify".parse().unwrap();
+        assert_eq!(action, Action::Unknown("fortify".to_string()));
+        assert_eq!(action.to_string(), "fortify");
+    }
+
+    #[test]
+    fn period_resolves_missing_field() {
+        let start_time = DateTime::from_millis(1_000);
+        let duration = Duration::from_secs(5);
+        let stop_time = start_time.checked_add(duration).unwrap();
+
+        let by_duration = Period {
+            start_time: Some(start_time),
+            stop_time: None,
+            duration: Some(duration),
+        };
+        assert_eq!(
+            by_duration.resolve().unwrap(),
+            ResolvedPeriod {
+                start_time,
+                stop_time,
+                duration
+            }
+        );
+
+        let by_start = Period {
+            start_time: None,
+            stop_time: Some(stop_time),
+            duration: Some(duration),
+        };
+        assert_eq!(
+            by_start.resolve().unwrap(),
+            ResolvedPeriod {
+                start_time,
+                stop_time,
+                duration
+            }
+        );
+
+        let by_stop = Period {
+            start_time: Some(start_time),
+            stop_time: Some(stop_time),
+            duration: None,
+        };
+        assert_eq!(
+            by_stop.resolve().unwrap(),
+            ResolvedPeriod {
+                start_time,
+                stop_time,
+                duration
+            }
+        );
+    }
+
+    #[test]
+    fn period_resolve_rejects_inconsistent_triple() {
+        let period = Period {
+            start_time: Some(DateTime::from_millis(1_000)),
+            stop_time: Some(DateTime::from_millis(2_000)),
+            duration: Some(Duration::from_secs(5)),
+        };
+        assert!(period.resolve().is_err());
+        assert!(period.check().is_err());
+    }
+
+    #[test]
+    fn period_resolve_requires_at_least_two_fields() {
+        let period = Period {
+            start_time: Some(DateTime::from_millis(1_000)),
+            stop_time: None,
+            duration: None,
+        };
+        assert!(period.resolve().is_err());
+
+        // Fewer than two fields isn't an inconsistency, so `check` doesn't reject it.
+        assert!(period.check().is_ok());
+    }
+
+    #[test]
+    fn period_contains_and_overlaps() {
+        let start_time = DateTime::from_millis(1_000);
+        let duration = Duration::from_secs(10);
+        let stop_time = start_time.checked_add(duration).unwrap();
+
+        let period = Period {
+            start_time: Some(start_time),
+            stop_time: Some(stop_time),
+            duration: None,
+        };
+
+        assert!(period.contains(start_time));
+        assert!(period.contains(stop_time));
+        assert!(!period.contains(stop_time.checked_add(Duration::from_secs(1)).unwrap()));
+
+        let overlapping = Period {
+            start_time: Some(stop_time),
+            stop_time: Some(stop_time.checked_add(duration).unwrap()),
+            duration: None,
+        };
+        assert!(period.overlaps(&overlapping));
+
+        let disjoint = Period {
+            start_time: Some(stop_time.checked_add(Duration::from_secs(1)).unwrap()),
+            stop_time: Some(stop_time.checked_add(duration).unwrap()),
+            duration: None,
+        };
+        assert!(!period.overlaps(&disjoint));
+    }
 }
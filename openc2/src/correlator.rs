@@ -0,0 +1,276 @@
+//! Producer-side request/response correlation.
+//!
+//! The spec's [`Message::command_id`] fallback to `headers.request_id` tells a *Consumer* how to
+//! tag its response; it doesn't give a *Producer* any machinery for matching an asynchronous
+//! response back to the command that produced it - the same correlation problem QAPI solves by
+//! tagging each command with an id and matching the reply. [`Correlator`] is that machinery. See
+//! [`Dispatcher`](crate::Dispatcher) for the consumer-side counterpart.
+
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+    pin::Pin,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use futures::channel::oneshot;
+
+use crate::{Body, CommandId, Content, Headers, Message, Response};
+
+type OutMessage<V> = Message<Headers, Body<Content<V>>>;
+
+struct Pending<V> {
+    /// Whether completion requires every name in `outstanding` to respond, i.e. the tracked
+    /// command's `headers.to` named explicit recipients. If `false`, the first response
+    /// completes the correlation.
+    expects_all: bool,
+    outstanding: HashSet<String>,
+    responses: Vec<Response<V>>,
+    deadline: Option<Instant>,
+    tx: Option<oneshot::Sender<Vec<Response<V>>>>,
+}
+
+/// Matches asynchronous [`Response`]s back to the outgoing [`Command`](crate::Command)s that
+/// produced them, via `command_id`/`headers.request_id`.
+///
+/// Call [`Self::track`] before sending a request to get a [`Correlation`] future for its
+/// eventual response(s), then feed every inbound message to [`Self::complete`] as it arrives.
+/// Like [`RateLimiter`](crate::RateLimiter), timing is caller-driven rather than
+/// self-scheduled: call [`Self::expire`] on your own schedule (e.g. an event loop tick) to give
+/// up on commands that have exceeded their deadline.
+pub struct Correlator<V> {
+    pending: Mutex<HashMap<CommandId, Pending<V>>>,
+    next_id: AtomicU64,
+}
+
+impl<V> Default for Correlator<V> {
+    fn default() -> Self {
+        Self {
+            pending: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(0),
+        }
+    }
+}
+
+impl<V> Correlator<V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn next_id(&self) -> CommandId {
+        format!("correlator-{}", self.next_id.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Registers `msg` - an outgoing request - for correlation, setting `headers.request_id` if
+    /// neither it nor the command's own `command_id` is already present, and returns a
+    /// [`Correlation`] that resolves once every recipient in `headers.to` has responded (or on
+    /// the first response, if `to` names none), or once `timeout` elapses per [`Self::expire`].
+    ///
+    /// Returns `None` if `msg`'s body isn't a [`Content::Request`], since only requests can be
+    /// correlated with a response.
+    pub fn track(&self, msg: &mut OutMessage<V>, timeout: Option<Duration>) -> Option<Correlation<V>> {
+        let Body::OpenC2(Content::Request(cmd)) = &mut msg.body else {
+            return None;
+        };
+
+        if cmd.command_id.is_none() && msg.headers.request_id.is_none() {
+            msg.headers.request_id = Some(self.next_id());
+        }
+
+        let command_id = msg.command_id()?.clone();
+        let (tx, rx) = oneshot::channel();
+
+        self.pending.lock().unwrap().insert(
+            command_id,
+            Pending {
+                expects_all: !msg.headers.to.is_empty(),
+                outstanding: msg.headers.to.iter().cloned().collect(),
+                responses: Vec::new(),
+                deadline: timeout.map(|d| Instant::now() + d),
+                tx: Some(tx),
+            },
+        );
+
+        Some(Correlation { rx })
+    }
+
+    /// Feeds an inbound message to the correlator.
+    ///
+    /// If `msg` is a [`Content::Response`] whose `headers.request_id` matches a pending
+    /// [`Self::track`]ed command, records it against that correlation and completes it if every
+    /// expected recipient has now answered. Responses that don't match anything pending - e.g. a
+    /// duplicate, or one that arrives after [`Self::expire`] already gave up on it - are ignored.
+    pub fn complete(&self, msg: OutMessage<V>) {
+        let Body::OpenC2(Content::Response(response)) = msg.body else {
+            return;
+        };
+
+        let Some(command_id) = msg.headers.request_id else {
+            return;
+        };
+
+        let mut pending = self.pending.lock().unwrap();
+        let Some(entry) = pending.get_mut(&command_id) else {
+            return;
+        };
+
+        if let Some(from) = &msg.headers.from {
+            entry.outstanding.remove(from);
+        }
+        entry.responses.push(response);
+
+        if !entry.expects_all || entry.outstanding.is_empty() {
+            Self::finish(&mut pending, &command_id);
+        }
+    }
+
+    /// Completes every pending correlation whose deadline has passed as of `now`, delivering
+    /// whatever responses it collected before giving up (possibly none).
+    pub fn expire(&self, now: Instant) {
+        let mut pending = self.pending.lock().unwrap();
+        let expired: Vec<CommandId> = pending
+            .iter()
+            .filter(|(_, entry)| entry.deadline.is_some_and(|deadline| now >= deadline))
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        for id in expired {
+            Self::finish(&mut pending, &id);
+        }
+    }
+
+    /// The number of commands currently awaiting a response.
+    pub fn len(&self) -> usize {
+        self.pending.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn finish(pending: &mut HashMap<CommandId, Pending<V>>, command_id: &CommandId) {
+        if let Some(mut entry) = pending.remove(command_id)
+            && let Some(tx) = entry.tx.take()
+        {
+            let _ = tx.send(entry.responses);
+        }
+    }
+}
+
+/// A handle returned by [`Correlator::track`] that resolves to the response(s) collected for a
+/// tracked command, once they're complete or the deadline [`Correlator::expire`] enforces elapses.
+pub struct Correlation<V> {
+    rx: oneshot::Receiver<Vec<Response<V>>>,
+}
+
+impl<V> Future for Correlation<V> {
+    type Output = Vec<Response<V>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match Pin::new(&mut self.rx).poll(cx) {
+            // The `Correlator` was dropped (or panicked) before completing this correlation.
+            Poll::Ready(Err(_)) => Poll::Ready(Vec::new()),
+            Poll::Ready(Ok(responses)) => Poll::Ready(responses),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::*;
+    use crate::{Action, Command, StatusCode, target::Features};
+
+    fn request(to: Vec<&str>) -> OutMessage<serde_json::Value> {
+        OutMessage {
+            headers: Headers {
+                to: to.into_iter().map(String::from).collect(),
+                ..Default::default()
+            },
+            content_type: Cow::Borrowed(OutMessage::<serde_json::Value>::CONTENT_TYPE),
+            body: Body::OpenC2(Content::Request(Command::new(
+                Action::Query,
+                Features::new(),
+            ))),
+            status_code: None,
+        }
+    }
+
+    fn response(request_id: &str, from: &str) -> OutMessage<serde_json::Value> {
+        OutMessage {
+            headers: Headers {
+                request_id: Some(request_id.to_string()),
+                from: Some(from.to_string()),
+                ..Default::default()
+            },
+            content_type: Cow::Borrowed(OutMessage::<serde_json::Value>::CONTENT_TYPE),
+            body: Body::OpenC2(Content::Response(Response::new(StatusCode::Ok))),
+            status_code: Some(StatusCode::Ok),
+        }
+    }
+
+    #[test]
+    fn assigns_request_id_when_missing() {
+        let correlator = Correlator::<serde_json::Value>::new();
+        let mut msg = request(vec![]);
+        assert!(msg.headers.request_id.is_none());
+
+        correlator.track(&mut msg, None).unwrap();
+        assert!(msg.headers.request_id.is_some());
+    }
+
+    #[test]
+    fn single_response_completes_without_explicit_recipients() {
+        futures::executor::block_on(async {
+            let correlator = Correlator::<serde_json::Value>::new();
+            let mut msg = request(vec![]);
+            let correlation = correlator.track(&mut msg, None).unwrap();
+
+            let request_id = msg.headers.request_id.clone().unwrap();
+            correlator.complete(response(&request_id, "consumer-a"));
+
+            assert_eq!(correlation.await.len(), 1);
+            assert!(correlator.is_empty());
+        });
+    }
+
+    #[test]
+    fn waits_for_every_named_recipient() {
+        futures::executor::block_on(async {
+            let correlator = Correlator::<serde_json::Value>::new();
+            let mut msg = request(vec!["consumer-a", "consumer-b"]);
+            let correlation = correlator.track(&mut msg, None).unwrap();
+
+            let request_id = msg.headers.request_id.clone().unwrap();
+            correlator.complete(response(&request_id, "consumer-a"));
+            assert!(!correlator.is_empty());
+
+            correlator.complete(response(&request_id, "consumer-b"));
+            assert_eq!(correlation.await.len(), 2);
+        });
+    }
+
+    #[test]
+    fn expire_completes_with_partial_responses() {
+        futures::executor::block_on(async {
+            let correlator = Correlator::<serde_json::Value>::new();
+            let mut msg = request(vec!["consumer-a", "consumer-b"]);
+            let correlation = correlator
+                .track(&mut msg, Some(Duration::from_secs(0)))
+                .unwrap();
+
+            let request_id = msg.headers.request_id.clone().unwrap();
+            correlator.complete(response(&request_id, "consumer-a"));
+
+            correlator.expire(Instant::now());
+            assert_eq!(correlation.await.len(), 1);
+        });
+    }
+}
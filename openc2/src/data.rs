@@ -5,8 +5,9 @@ use serde::{Deserialize, Serialize, de::Error as _};
 use serde_with::{DeserializeFromStr, SerializeDisplay, skip_serializing_none};
 use url::Url;
 
-use crate::{Action, IsEmpty, TargetType};
+use crate::{Action, Command, IsEmpty, TargetType, error::ValidationError};
 
+mod digest;
 mod ipnet;
 mod mac_addr;
 mod nsid;
@@ -15,15 +16,54 @@ mod time;
 mod value;
 mod version;
 
-pub use ipnet::{Ipv4Net, Ipv6Net};
+pub use digest::{Digest, Md5Digest, Sha1Digest, Sha256Digest};
+pub use ipnet::{IpNet, Ipv4Net, Ipv6Net};
 pub use mac_addr::{MacAddr, MacAddr6, MacAddr8};
 pub use nsid::Nsid;
 pub use time::{DateTime, Duration};
 pub use value::Value;
-pub use version::Version;
+#[cfg(feature = "postcard")]
+pub use value::PostcardValue;
+pub use version::{Version, VersionSet};
 
 pub type ActionTargets = IndexMap<Action, IndexSet<TargetType<'static>>>;
 
+/// Error returned when a [`Command`]'s action/target pair isn't present in an [`ActionTargets`]
+/// map, e.g. one advertised by a consumer's "query features" response.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("unsupported action-target pair: {action} - {target_type}")]
+pub struct UnsupportedPair {
+    pub action: Action,
+    pub target_type: String,
+}
+
+/// Extension trait for validating a [`Command`] against an advertised [`ActionTargets`] map, so
+/// a producer can reject an unsupported command locally instead of round-tripping to learn it's
+/// unimplemented.
+pub trait ActionTargetsExt {
+    /// Returns `Ok(())` if this map's entry for `command.action` contains `command.target`'s
+    /// type, or an [`UnsupportedPair`] naming the offending pair otherwise.
+    fn validate<V>(&self, command: &Command<V>) -> Result<(), UnsupportedPair>;
+}
+
+impl ActionTargetsExt for ActionTargets {
+    fn validate<V>(&self, command: &Command<V>) -> Result<(), UnsupportedPair> {
+        let target_type = command.target.kind();
+        let supported = self
+            .get(&command.action)
+            .is_some_and(|targets| targets.contains(&target_type));
+
+        if supported {
+            Ok(())
+        } else {
+            Err(UnsupportedPair {
+                action: command.action.clone(),
+                target_type: target_type.to_string(),
+            })
+        }
+    }
+}
+
 pub type CommandId = String;
 
 #[derive(
@@ -41,7 +81,40 @@ impl FromStr for DomainName {
     type Err = crate::error::ValidationError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self(s.to_string()))
+        if s.is_empty() {
+            return Err(ValidationError::new("domain name must not be empty"));
+        }
+
+        // IDNA/punycode normalization rejects malformed labels up front and gives us a
+        // canonical ASCII form to store and compare, so internationalized names round-trip.
+        let normalized = idna::domain_to_ascii(s)
+            .map_err(|e| ValidationError::new(format!("invalid domain name '{s}': {e:?}")))?;
+
+        if normalized.len() > 253 {
+            return Err(ValidationError::new(format!(
+                "domain name '{s}' exceeds 253 bytes"
+            )));
+        }
+
+        for label in normalized.split('.') {
+            if label.is_empty() {
+                return Err(ValidationError::new(format!(
+                    "domain name '{s}' has an empty label"
+                )));
+            }
+            if label.len() > 63 {
+                return Err(ValidationError::new(format!(
+                    "label '{label}' in domain name '{s}' exceeds 63 bytes"
+                )));
+            }
+            if label.starts_with('-') || label.ends_with('-') {
+                return Err(ValidationError::new(format!(
+                    "label '{label}' in domain name '{s}' must not start or end with a hyphen"
+                )));
+            }
+        }
+
+        Ok(Self(normalized))
     }
 }
 
@@ -60,7 +133,19 @@ impl FromStr for EmailAddr {
     type Err = crate::error::ValidationError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self(s.to_string()))
+        let (local, domain) = s.rsplit_once('@').ok_or_else(|| {
+            ValidationError::new(format!("email address '{s}' is missing '@'"))
+        })?;
+
+        if local.is_empty() {
+            return Err(ValidationError::new(format!(
+                "email address '{s}' has an empty local part"
+            )));
+        }
+
+        let domain: DomainName = domain.parse()?;
+
+        Ok(Self(format!("{local}@{domain}")))
     }
 }
 
@@ -166,9 +251,51 @@ pub enum Feature {
 #[skip_serializing_none]
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Hash)]
 pub struct Hashes {
-    pub md5: Option<String>,
-    pub sha1: Option<String>,
-    pub sha256: Option<String>,
+    pub md5: Option<Md5Digest>,
+    pub sha1: Option<Sha1Digest>,
+    pub sha256: Option<Sha256Digest>,
+}
+
+impl Hashes {
+    /// Computes the MD5, SHA-1, and SHA-256 digests of `bytes`.
+    pub fn of(bytes: &[u8]) -> Self {
+        use md5::{Digest as _, Md5};
+        use sha1::Sha1;
+        use sha2::Sha256;
+
+        Self {
+            md5: Some(Md5Digest::from(Md5::digest(bytes).into())),
+            sha1: Some(Sha1Digest::from(Sha1::digest(bytes).into())),
+            sha256: Some(Sha256Digest::from(Sha256::digest(bytes).into())),
+        }
+    }
+
+    /// Recomputes whichever digests are present in `self` from `bytes` and reports any that
+    /// don't match, e.g. before a consumer acts on an [`Artifact`](crate::target::Artifact)'s
+    /// embedded payload.
+    pub fn verify(&self, bytes: &[u8]) -> Result<(), ValidationError> {
+        let computed = Self::of(bytes);
+        let mut mismatched = Vec::new();
+
+        if self.md5.is_some() && self.md5 != computed.md5 {
+            mismatched.push("md5");
+        }
+        if self.sha1.is_some() && self.sha1 != computed.sha1 {
+            mismatched.push("sha1");
+        }
+        if self.sha256.is_some() && self.sha256 != computed.sha256 {
+            mismatched.push("sha256");
+        }
+
+        if mismatched.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationError::new(format!(
+                "content does not match declared hash(es): {}",
+                mismatched.join(", ")
+            )))
+        }
+    }
 }
 
 impl IsEmpty for Hashes {
@@ -201,3 +328,45 @@ impl ResponseType {
         !matches!(self, ResponseType::None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{DomainName, EmailAddr};
+
+    #[test]
+    fn domain_name_rejects_empty() {
+        let result: Result<DomainName, _> = "".parse();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn domain_name_rejects_label_too_long() {
+        let label = "a".repeat(64);
+        let result: Result<DomainName, _> = format!("{label}.example.com").parse();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn domain_name_rejects_hyphen_boundaries() {
+        let result: Result<DomainName, _> = "-bad.example.com".parse();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn domain_name_normalizes_internationalized_labels() {
+        let domain: DomainName = "münchen.de".parse().unwrap();
+        assert_eq!(domain.to_string(), "xn--mnchen-3ya.de");
+    }
+
+    #[test]
+    fn email_addr_requires_at_and_nonempty_local_part() {
+        assert!("no-at-sign".parse::<EmailAddr>().is_err());
+        assert!("@example.com".parse::<EmailAddr>().is_err());
+    }
+
+    #[test]
+    fn email_addr_parses_and_normalizes_domain() {
+        let email: EmailAddr = "user@München.de".parse().unwrap();
+        assert_eq!(email.to_string(), "user@xn--mnchen-3ya.de");
+    }
+}
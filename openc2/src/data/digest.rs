@@ -0,0 +1,103 @@
+//! Validated, fixed-length hex digests used by [`Hashes`](crate::Hashes).
+
+use std::{fmt, str::FromStr};
+
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+
+use crate::error::ValidationError;
+
+/// A validated digest: exactly `N` bytes, parsed from (and displayed as) lowercase hex.
+///
+/// Generic over the byte length so [`Md5Digest`], [`Sha1Digest`], and [`Sha256Digest`] share one
+/// implementation instead of three hand-rolled newtypes.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, SerializeDisplay, DeserializeFromStr)]
+pub struct Digest<const N: usize>([u8; N]);
+
+impl<const N: usize> Digest<N> {
+    /// The raw digest bytes.
+    pub fn as_bytes(&self) -> &[u8; N] {
+        &self.0
+    }
+}
+
+impl<const N: usize> fmt::Debug for Digest<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self}")
+    }
+}
+
+impl<const N: usize> fmt::Display for Digest<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in &self.0 {
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<const N: usize> FromStr for Digest<N> {
+    type Err = ValidationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != N * 2 {
+            return Err(ValidationError::new(format!(
+                "expected a {}-character hex digest, got {} characters",
+                N * 2,
+                s.len()
+            )));
+        }
+
+        let mut bytes = [0u8; N];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            let hex_pair = &s[i * 2..i * 2 + 2];
+            *byte = u8::from_str_radix(hex_pair, 16)
+                .map_err(|_| ValidationError::new(format!("'{hex_pair}' is not valid hex")))?;
+        }
+
+        Ok(Self(bytes))
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for Digest<N> {
+    fn from(bytes: [u8; N]) -> Self {
+        Self(bytes)
+    }
+}
+
+/// An MD5 digest: 16 bytes, 32 hex characters.
+pub type Md5Digest = Digest<16>;
+
+/// A SHA-1 digest: 20 bytes, 40 hex characters.
+pub type Sha1Digest = Digest<20>;
+
+/// A SHA-256 digest: 32 bytes, 64 hex characters.
+pub type Sha256Digest = Digest<32>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_valid_length_and_hex() {
+        let digest: Sha256Digest =
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+                .parse()
+                .unwrap();
+        assert_eq!(
+            digest.to_string(),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn rejects_wrong_length() {
+        let result: Result<Md5Digest, _> = "abcd".parse();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn rejects_non_hex_characters() {
+        let result: Result<Md5Digest, _> = "zzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzz".parse();
+        assert!(result.is_err());
+    }
+}
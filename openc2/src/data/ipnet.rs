@@ -4,13 +4,13 @@ use std::{
     str::FromStr,
 };
 
-use serde_with::{DeserializeFromStr, SerializeDisplay};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Visitor};
 
 use crate::error::ValidationError;
 
-#[derive(
-    Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, SerializeDisplay, DeserializeFromStr,
-)]
+/// Serializes as a `"a.b.c.d/len"`-style string for human-readable formats like JSON, and as the 4
+/// address octets followed by a `prefix_len` byte for binary formats like CBOR.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Ipv4Net {
     address: Ipv4Addr,
     /// The prefix length - this should never be 32.
@@ -32,6 +32,160 @@ impl Ipv4Net {
             },
         })
     }
+
+    /// The address portion of this CIDR block.
+    pub fn address(&self) -> Ipv4Addr {
+        self.address
+    }
+
+    /// The prefix length of this CIDR block, defaulting to 32 (a single host) when unset.
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len.unwrap_or(32)
+    }
+
+    /// Returns `true` if `address` falls within this CIDR block.
+    pub fn contains(&self, address: Ipv4Addr) -> bool {
+        let bits = 32 - u32::from(self.prefix_len());
+        let mask = u32::MAX.checked_shl(bits).unwrap_or(0);
+        (u32::from(self.address) & mask) == (u32::from(address) & mask)
+    }
+
+    /// Returns `true` if every address in `other` also falls within this CIDR block, i.e. `other`
+    /// is this block or a subnet of it.
+    pub fn contains_net(&self, other: &Self) -> bool {
+        if self.prefix_len() > other.prefix_len() {
+            return false;
+        }
+
+        self.contains(other.address)
+    }
+
+    /// Returns `true` if `self` and `other` share any address, i.e. one contains the other's base
+    /// address.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.contains(other.network().address) || other.contains(self.network().address)
+    }
+
+    /// This CIDR block with its host bits zeroed, so that equivalent CIDRs normalize equal.
+    pub fn network(&self) -> Self {
+        let bits = 32 - u32::from(self.prefix_len());
+        let mask = u32::MAX.checked_shl(bits).unwrap_or(0);
+        Self {
+            address: Ipv4Addr::from(u32::from(self.address) & mask),
+            prefix_len: self.prefix_len,
+        }
+    }
+
+    /// Collapses `nets` into the smallest equivalent set of non-overlapping CIDR blocks: subnets
+    /// fully covered by another entry are dropped, and sibling pairs (e.g. `10.0.0.0/25` and
+    /// `10.0.0.128/25`) are merged into their common parent until no further merge applies.
+    pub fn aggregate(nets: &[Self]) -> Vec<Self> {
+        let mut blocks: Vec<(u32, u8)> = nets
+            .iter()
+            .map(|net| {
+                let net = net.network();
+                (u32::from(net.address), net.prefix_len())
+            })
+            .collect();
+        blocks.sort_unstable();
+        blocks.dedup();
+
+        let mut kept: Vec<(u32, u8)> = Vec::new();
+        for (base, prefix) in blocks {
+            let contained = kept.iter().any(|&(kbase, kprefix)| {
+                kprefix <= prefix && (kbase & v4_mask(kprefix)) == (base & v4_mask(kprefix))
+            });
+            if !contained {
+                kept.push((base, prefix));
+            }
+        }
+
+        loop {
+            kept.sort_unstable();
+            let mut merged = Vec::with_capacity(kept.len());
+            let mut changed = false;
+            let mut i = 0;
+            while i < kept.len() {
+                if let Some(&(base2, prefix2)) = kept.get(i + 1) {
+                    let (base1, prefix1) = kept[i];
+                    if prefix1 == prefix2 && prefix1 > 0 {
+                        let parent_prefix = prefix1 - 1;
+                        let parent_mask = v4_mask(parent_prefix);
+                        let sibling_bit = 1u32 << (32 - u32::from(prefix1));
+                        if (base1 & parent_mask) == (base2 & parent_mask)
+                            && (base1 ^ base2) == sibling_bit
+                        {
+                            merged.push((base1 & parent_mask, parent_prefix));
+                            i += 2;
+                            changed = true;
+                            continue;
+                        }
+                    }
+                }
+                merged.push(kept[i]);
+                i += 1;
+            }
+
+            kept = merged;
+            if !changed {
+                break;
+            }
+        }
+
+        kept.into_iter()
+            .map(|(base, prefix)| {
+                Self::new(Ipv4Addr::from(base), Some(prefix))
+                    .expect("aggregate only produces prefixes between 0 and 32")
+            })
+            .collect()
+    }
+}
+
+/// The network mask for an IPv4 prefix length, with the `prefix == 0` shift-overflow handled.
+fn v4_mask(prefix_len: u8) -> u32 {
+    u32::MAX.checked_shl(32 - u32::from(prefix_len)).unwrap_or(0)
+}
+
+impl Serialize for Ipv4Net {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            let mut bytes = [0u8; 5];
+            bytes[..4].copy_from_slice(&self.address.octets());
+            bytes[4] = self.prefix_len();
+            serializer.serialize_bytes(&bytes)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Ipv4Net {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            String::deserialize(deserializer)?
+                .parse()
+                .map_err(serde::de::Error::custom)
+        } else {
+            struct BytesVisitor;
+
+            impl<'de> Visitor<'de> for BytesVisitor {
+                type Value = Ipv4Net;
+
+                fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    write!(f, "4 address octets followed by a prefix_len byte")
+                }
+
+                fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                    let [a, b, c, d, prefix_len]: [u8; 5] =
+                        v.try_into().map_err(|_| E::invalid_length(v.len(), &self))?;
+                    Ipv4Net::new(Ipv4Addr::new(a, b, c, d), Some(prefix_len))
+                        .map_err(serde::de::Error::custom)
+                }
+            }
+
+            deserializer.deserialize_bytes(BytesVisitor)
+        }
+    }
 }
 
 impl fmt::Debug for Ipv4Net {
@@ -80,9 +234,9 @@ impl From<Ipv4Addr> for Ipv4Net {
     }
 }
 
-#[derive(
-    Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, SerializeDisplay, DeserializeFromStr,
-)]
+/// Serializes as an `"a:b::c/len"`-style string for human-readable formats like JSON, and as the
+/// 16 address octets followed by a `prefix_len` byte for binary formats like CBOR.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Ipv6Net {
     address: Ipv6Addr,
     /// The prefix length - this should never be 128.
@@ -104,6 +258,164 @@ impl Ipv6Net {
             },
         })
     }
+
+    /// The address portion of this CIDR block.
+    pub fn address(&self) -> Ipv6Addr {
+        self.address
+    }
+
+    /// The prefix length of this CIDR block, defaulting to 128 (a single host) when unset.
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len.unwrap_or(128)
+    }
+
+    /// Returns `true` if `address` falls within this CIDR block.
+    pub fn contains(&self, address: Ipv6Addr) -> bool {
+        let bits = 128 - u32::from(self.prefix_len());
+        let mask = u128::MAX.checked_shl(bits).unwrap_or(0);
+        (u128::from(self.address) & mask) == (u128::from(address) & mask)
+    }
+
+    /// Returns `true` if every address in `other` also falls within this CIDR block, i.e. `other`
+    /// is this block or a subnet of it.
+    pub fn contains_net(&self, other: &Self) -> bool {
+        if self.prefix_len() > other.prefix_len() {
+            return false;
+        }
+
+        self.contains(other.address)
+    }
+
+    /// Returns `true` if `self` and `other` share any address, i.e. one contains the other's base
+    /// address.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.contains(other.network().address) || other.contains(self.network().address)
+    }
+
+    /// This CIDR block with its host bits zeroed, so that equivalent CIDRs normalize equal.
+    pub fn network(&self) -> Self {
+        let bits = 128 - u32::from(self.prefix_len());
+        let mask = u128::MAX.checked_shl(bits).unwrap_or(0);
+        Self {
+            address: Ipv6Addr::from(u128::from(self.address) & mask),
+            prefix_len: self.prefix_len,
+        }
+    }
+
+    /// Collapses `nets` into the smallest equivalent set of non-overlapping CIDR blocks: subnets
+    /// fully covered by another entry are dropped, and sibling pairs are merged into their common
+    /// parent until no further merge applies.
+    pub fn aggregate(nets: &[Self]) -> Vec<Self> {
+        let mut blocks: Vec<(u128, u8)> = nets
+            .iter()
+            .map(|net| {
+                let net = net.network();
+                (u128::from(net.address), net.prefix_len())
+            })
+            .collect();
+        blocks.sort_unstable();
+        blocks.dedup();
+
+        let mut kept: Vec<(u128, u8)> = Vec::new();
+        for (base, prefix) in blocks {
+            let contained = kept.iter().any(|&(kbase, kprefix)| {
+                kprefix <= prefix && (kbase & v6_mask(kprefix)) == (base & v6_mask(kprefix))
+            });
+            if !contained {
+                kept.push((base, prefix));
+            }
+        }
+
+        loop {
+            kept.sort_unstable();
+            let mut merged = Vec::with_capacity(kept.len());
+            let mut changed = false;
+            let mut i = 0;
+            while i < kept.len() {
+                if let Some(&(base2, prefix2)) = kept.get(i + 1) {
+                    let (base1, prefix1) = kept[i];
+                    if prefix1 == prefix2 && prefix1 > 0 {
+                        let parent_prefix = prefix1 - 1;
+                        let parent_mask = v6_mask(parent_prefix);
+                        let sibling_bit = 1u128 << (128 - u32::from(prefix1));
+                        if (base1 & parent_mask) == (base2 & parent_mask)
+                            && (base1 ^ base2) == sibling_bit
+                        {
+                            merged.push((base1 & parent_mask, parent_prefix));
+                            i += 2;
+                            changed = true;
+                            continue;
+                        }
+                    }
+                }
+                merged.push(kept[i]);
+                i += 1;
+            }
+
+            kept = merged;
+            if !changed {
+                break;
+            }
+        }
+
+        kept.into_iter()
+            .map(|(base, prefix)| {
+                Self::new(Ipv6Addr::from(base), Some(prefix))
+                    .expect("aggregate only produces prefixes between 0 and 128")
+            })
+            .collect()
+    }
+}
+
+/// The network mask for an IPv6 prefix length, with the `prefix == 0` shift-overflow handled.
+fn v6_mask(prefix_len: u8) -> u128 {
+    u128::MAX
+        .checked_shl(128 - u32::from(prefix_len))
+        .unwrap_or(0)
+}
+
+impl Serialize for Ipv6Net {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            let mut bytes = [0u8; 17];
+            bytes[..16].copy_from_slice(&self.address.octets());
+            bytes[16] = self.prefix_len();
+            serializer.serialize_bytes(&bytes)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Ipv6Net {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            String::deserialize(deserializer)?
+                .parse()
+                .map_err(serde::de::Error::custom)
+        } else {
+            struct BytesVisitor;
+
+            impl<'de> Visitor<'de> for BytesVisitor {
+                type Value = Ipv6Net;
+
+                fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    write!(f, "16 address octets followed by a prefix_len byte")
+                }
+
+                fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                    if v.len() != 17 {
+                        return Err(E::invalid_length(v.len(), &self));
+                    }
+                    let mut octets = [0u8; 16];
+                    octets.copy_from_slice(&v[..16]);
+                    Ipv6Net::new(Ipv6Addr::from(octets), Some(v[16])).map_err(serde::de::Error::custom)
+                }
+            }
+
+            deserializer.deserialize_bytes(BytesVisitor)
+        }
+    }
 }
 
 impl fmt::Debug for Ipv6Net {
@@ -151,3 +463,65 @@ impl From<Ipv6Addr> for Ipv6Net {
         }
     }
 }
+
+/// An IPv4 or IPv6 CIDR block, for callers that need to store or match a network without
+/// statically committing to an address family - much like [`std::net::IpAddr`] does for bare
+/// addresses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum IpNet {
+    V4(Ipv4Net),
+    V6(Ipv6Net),
+}
+
+impl IpNet {
+    /// Returns `true` if `addr` falls within this CIDR block. Always `false` if `addr` is not in
+    /// the same address family as this block.
+    pub fn contains(&self, addr: std::net::IpAddr) -> bool {
+        match (self, addr) {
+            (Self::V4(net), std::net::IpAddr::V4(addr)) => net.contains(addr),
+            (Self::V6(net), std::net::IpAddr::V6(addr)) => net.contains(addr),
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if `self` and `other` share any address. Always `false` if they're in
+    /// different address families.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::V4(a), Self::V4(b)) => a.overlaps(b),
+            (Self::V6(a), Self::V6(b)) => a.overlaps(b),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for IpNet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::V4(net) => net.fmt(f),
+            Self::V6(net) => net.fmt(f),
+        }
+    }
+}
+
+impl FromStr for IpNet {
+    type Err = ValidationError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let addr = s.split_once('/').map_or(s, |(addr, _)| addr);
+
+        if addr.contains(':') {
+            s.parse().map(Self::V6)
+        } else {
+            s.parse().map(Self::V4)
+        }
+    }
+}
+
+impl From<std::net::IpAddr> for IpNet {
+    fn from(address: std::net::IpAddr) -> Self {
+        match address {
+            std::net::IpAddr::V4(address) => Self::V4(address.into()),
+            std::net::IpAddr::V6(address) => Self::V6(address.into()),
+        }
+    }
+}
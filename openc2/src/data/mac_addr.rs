@@ -1,19 +1,69 @@
 use std::{fmt, str::FromStr};
 
 pub use macaddr::{MacAddr6, MacAddr8};
-use serde_with::{DeserializeFromStr, SerializeDisplay};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Visitor};
 
 use crate::error::ValidationError;
 
 /// A MAC address, either in *EUI-48* or *EUI-64* format.
-#[derive(
-    Debug, Hash, Eq, PartialEq, Ord, PartialOrd, Copy, Clone, SerializeDisplay, DeserializeFromStr,
-)]
+///
+/// Serializes as a `"aa:bb:cc:dd:ee:ff"`-style string for human-readable formats like JSON, and as
+/// the 6 or 8 raw address octets for binary formats like CBOR.
+#[derive(Debug, Hash, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
 pub enum MacAddr {
     V6(MacAddr6),
     V8(MacAddr8),
 }
 
+impl MacAddr {
+    fn as_bytes(&self) -> &[u8] {
+        match self {
+            MacAddr::V6(addr) => addr.as_bytes(),
+            MacAddr::V8(addr) => addr.as_bytes(),
+        }
+    }
+}
+
+impl Serialize for MacAddr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serializer.serialize_bytes(self.as_bytes())
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for MacAddr {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            String::deserialize(deserializer)?
+                .parse()
+                .map_err(serde::de::Error::custom)
+        } else {
+            struct BytesVisitor;
+
+            impl<'de> Visitor<'de> for BytesVisitor {
+                type Value = MacAddr;
+
+                fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    write!(f, "6 or 8 raw MAC address octets")
+                }
+
+                fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                    match v.len() {
+                        6 => Ok(MacAddr::V6(MacAddr6::from(<[u8; 6]>::try_from(v).unwrap()))),
+                        8 => Ok(MacAddr::V8(MacAddr8::from(<[u8; 8]>::try_from(v).unwrap()))),
+                        other => Err(E::invalid_length(other, &self)),
+                    }
+                }
+            }
+
+            deserializer.deserialize_bytes(BytesVisitor)
+        }
+    }
+}
+
 impl fmt::Display for MacAddr {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -76,4 +126,12 @@ mod tests {
         let addr: MacAddr6 = "01:23:45:67:89:ab".parse().unwrap();
         assert_eq!(addr.to_string(), "01:23:45:67:89:AB");
     }
+
+    #[cfg(feature = "cbor")]
+    #[test]
+    fn round_trips_as_bytes_over_cbor() {
+        let addr: MacAddr = "01:23:45:67:89:ab".parse().unwrap();
+        let bytes = serde_cbor::to_vec(&addr).unwrap();
+        assert_eq!(serde_cbor::from_slice::<MacAddr>(&bytes).unwrap(), addr);
+    }
 }
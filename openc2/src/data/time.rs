@@ -5,8 +5,10 @@ use std::{
 
 use serde::{Deserialize, Serialize};
 
+use crate::error::ValidationError;
+
 /// Epoch milliseconds
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[serde(transparent)]
 pub struct DateTime(u64);
 
@@ -33,6 +35,210 @@ impl DateTime {
     pub fn as_millis(&self) -> u64 {
         self.0
     }
+
+    /// Returns `self + rhs`, or `None` on overflow.
+    pub fn checked_add(self, rhs: Duration) -> Option<Self> {
+        self.0.checked_add(rhs.0).map(Self)
+    }
+
+    /// Returns the [`Duration`] between `self` and `earlier`, or `None` if `earlier` is after
+    /// `self`.
+    pub fn checked_duration_since(self, earlier: Self) -> Option<Duration> {
+        self.0.checked_sub(earlier.0).map(Duration)
+    }
+
+    /// Returns `self - rhs`, or `None` if `rhs` is longer than the time since the Unix epoch.
+    pub fn checked_sub(self, rhs: Duration) -> Option<Self> {
+        self.0.checked_sub(rhs.0).map(Self)
+    }
+
+    /// Returns the [`Duration`] between `self` and `earlier`, or `None` if `earlier` is after
+    /// `self`. An alias for [`checked_duration_since`](Self::checked_duration_since) under the
+    /// name callers computing command expiry windows are likely to reach for first.
+    pub fn duration_since(&self, earlier: Self) -> Option<Duration> {
+        self.checked_duration_since(earlier)
+    }
+
+    /// Returns `self + rhs`, saturating at [`u64::MAX`] milliseconds on overflow.
+    pub fn saturating_add(self, rhs: Duration) -> Self {
+        Self(self.0.saturating_add(rhs.0))
+    }
+
+    /// Returns `self - rhs`, saturating at the Unix epoch if `rhs` is longer than the time since
+    /// the Unix epoch.
+    pub fn saturating_sub(self, rhs: Duration) -> Self {
+        Self(self.0.saturating_sub(rhs.0))
+    }
+
+    /// Parses an RFC 3339 / ISO 8601 timestamp such as `2024-01-02T03:04:05.678Z` or
+    /// `2024-01-02T03:04:05+01:00`.
+    ///
+    /// Only timestamps at or after the Unix epoch (1970-01-01T00:00:00Z) are accepted, since
+    /// earlier ones can't be represented by this type.
+    pub fn from_rfc3339(s: &str) -> Result<Self, ValidationError> {
+        let (date, rest) = s
+            .split_once(['T', 't'])
+            .ok_or_else(|| ValidationError::new("missing 'T' date/time separator"))?;
+
+        let mut date = date.split('-');
+        let (y, m, d) = (
+            date.next().ok_or_else(|| ValidationError::new("missing year"))?,
+            date.next().ok_or_else(|| ValidationError::new("missing month"))?,
+            date.next().ok_or_else(|| ValidationError::new("missing day"))?,
+        );
+        if date.next().is_some() {
+            return Err(ValidationError::new("malformed date"));
+        }
+        let y: i64 = y
+            .parse()
+            .map_err(|_| ValidationError::new("invalid year"))?;
+        let m: u32 = m
+            .parse()
+            .map_err(|_| ValidationError::new("invalid month"))?;
+        let d: u32 = d.parse().map_err(|_| ValidationError::new("invalid day"))?;
+        if !(1..=12).contains(&m) {
+            return Err(ValidationError::new("month must be between 1 and 12"));
+        }
+        if !(1..=days_in_month(y, m)).contains(&d) {
+            return Err(ValidationError::new("day is out of range for its month"));
+        }
+        if y < 1970 {
+            return Err(ValidationError::new("timestamps before 1970 are not supported"));
+        }
+
+        let (time, offset_minutes) = split_offset(rest)?;
+        let mut time = time.split(':');
+        let (hh, mm) = (
+            time.next().ok_or_else(|| ValidationError::new("missing hour"))?,
+            time.next().ok_or_else(|| ValidationError::new("missing minute"))?,
+        );
+        let ss = time.next().unwrap_or("0");
+        if time.next().is_some() {
+            return Err(ValidationError::new("malformed time"));
+        }
+        let hh: u64 = hh.parse().map_err(|_| ValidationError::new("invalid hour"))?;
+        let mm: u64 = mm
+            .parse()
+            .map_err(|_| ValidationError::new("invalid minute"))?;
+        let (ss, millis) = ss.split_once('.').unwrap_or((ss, "0"));
+        let ss: u64 = ss.parse().map_err(|_| ValidationError::new("invalid second"))?;
+        let millis: u64 = format!("{millis:0<3}")[..3]
+            .parse()
+            .map_err(|_| ValidationError::new("invalid fractional second"))?;
+        if hh > 23 || mm > 59 || ss > 60 {
+            return Err(ValidationError::new("time of day out of range"));
+        }
+
+        let days = days_from_civil(y, m, d);
+        let day_millis = ((hh * 60 + mm) * 60 + ss) * 1000 + millis;
+        let total_millis = days
+            .checked_mul(86_400_000)
+            .and_then(|d| d.checked_add(day_millis))
+            .and_then(|millis| millis.checked_sub((offset_minutes * 60_000) as i64))
+            .ok_or_else(|| ValidationError::new("timestamp overflow"))?;
+
+        Ok(Self(
+            total_millis
+                .try_into()
+                .map_err(|_| ValidationError::new("timestamps before 1970 are not supported"))?,
+        ))
+    }
+
+    /// Formats this timestamp as RFC 3339 / ISO 8601, e.g. `2024-01-02T03:04:05.678Z`.
+    pub fn to_rfc3339(&self) -> String {
+        let days = (self.0 / 86_400_000) as i64;
+        let day_millis = self.0 % 86_400_000;
+        let (y, m, d) = civil_from_days(days);
+
+        let hh = day_millis / 3_600_000;
+        let mm = (day_millis / 60_000) % 60;
+        let ss = (day_millis / 1000) % 60;
+        let millis = day_millis % 1000;
+
+        if millis == 0 {
+            format!("{y:04}-{m:02}-{d:02}T{hh:02}:{mm:02}:{ss:02}Z")
+        } else {
+            format!("{y:04}-{m:02}-{d:02}T{hh:02}:{mm:02}:{ss:02}.{millis:03}Z")
+        }
+    }
+}
+
+/// Splits the time-of-day portion of an RFC 3339 timestamp from its trailing `Z` or `+HH:MM`/
+/// `-HH:MM` offset, returning the offset in minutes (positive means ahead of UTC).
+fn split_offset(s: &str) -> Result<(&str, i64), ValidationError> {
+    if let Some(time) = s.strip_suffix(['Z', 'z']) {
+        return Ok((time, 0));
+    }
+
+    // Find the sign of the offset, skipping the leading digits of the time of day.
+    let sign_index = s
+        .rfind(['+', '-'])
+        .ok_or_else(|| ValidationError::new("missing UTC offset or 'Z'"))?;
+    let (time, offset) = s.split_at(sign_index);
+    let sign = if offset.starts_with('-') { -1 } else { 1 };
+    let (oh, om) = offset[1..]
+        .split_once(':')
+        .ok_or_else(|| ValidationError::new("malformed UTC offset"))?;
+    let oh: i64 = oh
+        .parse()
+        .map_err(|_| ValidationError::new("invalid offset hours"))?;
+    let om: i64 = om
+        .parse()
+        .map_err(|_| ValidationError::new("invalid offset minutes"))?;
+
+    Ok((time, sign * (oh * 60 + om)))
+}
+
+/// Days in `month` of `year`, accounting for leap years.
+fn days_in_month(year: i64, month: u32) -> u32 {
+    const LENGTHS: [u32; 12] = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+    if month == 2 && (year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)) {
+        29
+    } else {
+        LENGTHS[(month - 1) as usize]
+    }
+}
+
+/// Howard Hinnant's `days_from_civil`: the number of days since the Unix epoch for a given civil
+/// (year, month, day), valid across the full proleptic Gregorian calendar.
+fn days_from_civil(y: i64, m: u32, d: u32) -> i64 {
+    let y = y - i64::from(m <= 2);
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let mp = i64::from(if m > 2 { m - 3 } else { m + 9 });
+    let doy = (153 * mp + 2) / 5 + i64::from(d) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Howard Hinnant's `civil_from_days`: the inverse of [`days_from_civil`].
+fn civil_from_days(z: i64) -> (i64, u32, u32) {
+    let z = z + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let y = yoe + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    (y + i64::from(m <= 2), m, d)
+}
+
+/// `#[serde(with = "rfc3339")]` support for serializing a [`DateTime`] as an RFC 3339 string
+/// instead of the default epoch-milliseconds integer.
+pub mod rfc3339 {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use super::DateTime;
+
+    pub fn serialize<S: Serializer>(value: &DateTime, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&value.to_rfc3339())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime, D::Error> {
+        DateTime::from_rfc3339(&String::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+    }
 }
 
 impl ops::Add<Duration> for DateTime {
@@ -75,6 +281,26 @@ impl Duration {
     pub fn as_millis(&self) -> u64 {
         self.0
     }
+
+    /// Returns `self + rhs`, or `None` on overflow.
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        self.0.checked_add(rhs.0).map(Self)
+    }
+
+    /// Returns `self - rhs`, or `None` if `rhs` is longer than `self`.
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.0.checked_sub(rhs.0).map(Self)
+    }
+
+    /// Returns `self + rhs`, saturating at [`u64::MAX`] milliseconds on overflow.
+    pub fn saturating_add(self, rhs: Self) -> Self {
+        Self(self.0.saturating_add(rhs.0))
+    }
+
+    /// Returns `self - rhs`, saturating at zero if `rhs` is longer than `self`.
+    pub fn saturating_sub(self, rhs: Self) -> Self {
+        Self(self.0.saturating_sub(rhs.0))
+    }
 }
 
 impl fmt::Debug for Duration {
@@ -98,3 +324,55 @@ impl ops::Sub for Duration {
         Duration(self.0.checked_sub(rhs.0).expect("rhs is longer than lhs"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rfc3339_round_trips_through_millis() {
+        let dt = DateTime::from_rfc3339("2024-01-02T03:04:05.678Z").unwrap();
+        assert_eq!(dt.as_millis(), 1_704_164_645_678);
+        assert_eq!(dt.to_rfc3339(), "2024-01-02T03:04:05.678Z");
+    }
+
+    #[test]
+    fn rfc3339_without_fractional_seconds_omits_them() {
+        let dt = DateTime::from_rfc3339("1970-01-01T00:00:00Z").unwrap();
+        assert_eq!(dt.as_millis(), 0);
+        assert_eq!(dt.to_rfc3339(), "1970-01-01T00:00:00Z");
+    }
+
+    #[test]
+    fn rfc3339_offset_normalizes_to_utc() {
+        let plus = DateTime::from_rfc3339("2024-01-02T04:04:05+01:00").unwrap();
+        let utc = DateTime::from_rfc3339("2024-01-02T03:04:05Z").unwrap();
+        assert_eq!(plus, utc);
+    }
+
+    #[test]
+    fn rfc3339_rejects_years_before_epoch() {
+        assert!(DateTime::from_rfc3339("1969-12-31T23:59:59Z").is_err());
+    }
+
+    #[test]
+    fn rfc3339_rejects_invalid_month_and_day() {
+        assert!(DateTime::from_rfc3339("2024-13-01T00:00:00Z").is_err());
+        assert!(DateTime::from_rfc3339("2024-02-30T00:00:00Z").is_err());
+    }
+
+    #[test]
+    fn saturating_sub_clamps_at_epoch_instead_of_panicking() {
+        let early = DateTime::from_millis(100);
+        let long = Duration::from_millis(200);
+        assert_eq!(early.saturating_sub(long), DateTime::from_millis(0));
+    }
+
+    #[test]
+    fn duration_since_is_none_when_earlier_is_in_the_future() {
+        let now = DateTime::from_millis(100);
+        let later = DateTime::from_millis(200);
+        assert_eq!(now.duration_since(later), None);
+        assert_eq!(later.duration_since(now), Some(Duration::from_millis(100)));
+    }
+}
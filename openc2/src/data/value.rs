@@ -39,3 +39,46 @@ impl Value for serde_cbor::Value {
         serde_cbor::value::from_value(self)
     }
 }
+
+#[cfg(feature = "msgpack")]
+impl Value for rmpv::Value {
+    type Error = rmpv::ext::Error;
+
+    fn from_typed<V: Serialize>(value: &V) -> Result<Self, Self::Error> {
+        rmpv::ext::to_value(value)
+    }
+
+    fn to_typed<T: DeserializeOwned>(self) -> Result<T, Self::Error> {
+        rmpv::ext::from_value(self)
+    }
+}
+
+/// A [`Value`] backed by the [postcard] wire format.
+///
+/// Unlike JSON/CBOR/MessagePack, postcard has no self-describing value type, so this wraps the
+/// already-encoded bytes of a single message body.
+///
+/// [postcard]: https://docs.rs/postcard
+#[cfg(feature = "postcard")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PostcardValue(Vec<u8>);
+
+#[cfg(feature = "postcard")]
+impl PostcardValue {
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+#[cfg(feature = "postcard")]
+impl Value for PostcardValue {
+    type Error = postcard::Error;
+
+    fn from_typed<V: Serialize>(value: &V) -> Result<Self, Self::Error> {
+        Ok(Self(postcard::to_allocvec(value)?))
+    }
+
+    fn to_typed<T: DeserializeOwned>(self) -> Result<T, Self::Error> {
+        postcard::from_bytes(&self.0)
+    }
+}
@@ -1,5 +1,7 @@
 use std::{fmt, str::FromStr};
 
+use indexmap::IndexSet;
+use serde::{Deserialize, Serialize};
 use serde_with::{DeserializeFromStr, SerializeDisplay};
 
 use crate::error::ValidationError;
@@ -13,6 +15,12 @@ pub struct Version {
     pub minor: u8,
 }
 
+impl Version {
+    pub const fn new(major: u8, minor: u8) -> Self {
+        Self { major, minor }
+    }
+}
+
 impl fmt::Debug for Version {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{self}")
@@ -41,3 +49,71 @@ impl FromStr for Version {
         Ok(Version { major, minor })
     }
 }
+
+/// An ordered list of [`Version`]s, listed most-preferred-first.
+///
+/// This is used during version negotiation: a client advertises the versions it can speak in
+/// preference order, and the peer picks the highest-ranked entry it also supports.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct VersionSet(Vec<Version>);
+
+impl VersionSet {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Version> {
+        self.0.iter()
+    }
+
+    /// Returns the highest-preference version in this set that is also present in `supported`,
+    /// or `None` if there is no overlap.
+    pub fn highest_mutual(&self, supported: &IndexSet<Version>) -> Option<Version> {
+        self.0.iter().find(|v| supported.contains(v)).copied()
+    }
+}
+
+impl FromIterator<Version> for VersionSet {
+    fn from_iter<T: IntoIterator<Item = Version>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl IntoIterator for VersionSet {
+    type Item = Version;
+    type IntoIter = std::vec::IntoIter<Version>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl crate::IsEmpty for VersionSet {
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn highest_mutual_prefers_client_order() {
+        let client = VersionSet::from_iter([Version::new(2, 0), Version::new(1, 0)]);
+        let supported: IndexSet<Version> = [Version::new(1, 0), Version::new(2, 0)]
+            .into_iter()
+            .collect();
+
+        assert_eq!(client.highest_mutual(&supported), Some(Version::new(2, 0)));
+    }
+
+    #[test]
+    fn highest_mutual_none_on_no_overlap() {
+        let client = VersionSet::from_iter([Version::new(3, 0)]);
+        let supported: IndexSet<Version> = [Version::new(1, 0)].into_iter().collect();
+
+        assert_eq!(client.highest_mutual(&supported), None);
+    }
+}
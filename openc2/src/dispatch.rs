@@ -0,0 +1,109 @@
+//! A minimal consumer-side request router, keyed on the `(Action, TargetType)` pair a
+//! [`Command`] carries.
+
+use std::collections::BTreeMap;
+
+use crate::{
+    Action, Body, Check, Command, CommandId, Content, Error, Headers, Message, Response,
+    ResponseType, TargetType,
+};
+
+/// Handles a single `(Action, TargetType)` pair's commands.
+///
+/// A blanket impl covers plain closures, so most handlers don't need a named type:
+///
+/// ```ignore
+/// dispatcher.with_handler(Action::Deny, TargetType::Ipv4Net, |cmd: &Command<Value>| {
+///     Response::new(StatusCode::Ok)
+/// });
+/// ```
+pub trait CommandHandler<V> {
+    fn handle(&self, cmd: &Command<V>) -> Response<V>;
+}
+
+impl<V, F: Fn(&Command<V>) -> Response<V>> CommandHandler<V> for F {
+    fn handle(&self, cmd: &Command<V>) -> Response<V> {
+        self(cmd)
+    }
+}
+
+/// Routes inbound [`Message`]s to the [`CommandHandler`] registered for their `(Action,
+/// TargetType)` pair.
+///
+/// Unlike [`openc2_consumer`](https://docs.rs/openc2_consumer)'s [`Consume`](https://docs.rs/openc2_consumer/latest/openc2_consumer/trait.Consume.html)
+/// trait, which fans a command out to a set of negotiated consumers and merges their responses,
+/// `Dispatcher` is for the single-consumer case: a straight lookup from pair to handler, with no
+/// negotiation or response merging.
+pub struct Dispatcher<V> {
+    handlers: BTreeMap<(Action, TargetType<'static>), Box<dyn CommandHandler<V> + Send + Sync>>,
+}
+
+impl<V> Default for Dispatcher<V> {
+    fn default() -> Self {
+        Self {
+            handlers: BTreeMap::new(),
+        }
+    }
+}
+
+impl<V> Dispatcher<V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `handler` for the given `(action, target_type)` pair, replacing whatever was
+    /// previously registered for that pair.
+    pub fn with_handler(
+        mut self,
+        action: Action,
+        target_type: TargetType<'static>,
+        handler: impl CommandHandler<V> + Send + Sync + 'static,
+    ) -> Self {
+        self.handlers
+            .insert((action, target_type), Box::new(handler));
+        self
+    }
+
+    /// Extracts the [`Command`] from `msg`, checks it, routes it to the matching handler, and
+    /// wraps the handler's [`Response`] back into a [`Message`].
+    ///
+    /// Returns `None` if the command's `response_requested` is [`ResponseType::None`], per the
+    /// spec's instruction that such commands receive no response.
+    pub fn dispatch(
+        &self,
+        msg: Message<Headers, Body<Content<V>>>,
+    ) -> Option<Message<Headers, Body<Content<V>>>> {
+        let request_id = msg.command_id().cloned();
+
+        let cmd = match msg.check().and_then(|()| Command::try_from(msg.body)) {
+            Ok(cmd) => cmd,
+            Err(err) => return Some(tag_response(err.into(), request_id)),
+        };
+
+        let response_requested = cmd
+            .args
+            .response_requested
+            .unwrap_or(ResponseType::Complete);
+
+        let pair = (cmd.action.clone(), cmd.target.kind().into_owned());
+        let response = match self.handlers.get(&pair) {
+            Some(handler) => handler.handle(&cmd),
+            None => Error::not_implemented_pair(pair.0, &pair.1).into(),
+        };
+
+        if matches!(response_requested, ResponseType::None) {
+            return None;
+        }
+
+        Some(tag_response(response, request_id))
+    }
+}
+
+fn tag_response<V>(
+    response: Response<V>,
+    request_id: Option<CommandId>,
+) -> Message<Headers, Body<Content<V>>> {
+    let mut msg: Message<Headers, Body<Content<V>>> = Content::Response(response).into();
+    msg.headers.request_id = request_id;
+    msg
+}
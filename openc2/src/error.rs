@@ -6,6 +6,7 @@ use std::{
 };
 
 use from_variants::FromVariants;
+use serde::ser::{Serialize, SerializeMap, Serializer};
 
 use crate::{Action, Response, StatusCode, TargetType};
 
@@ -42,6 +43,11 @@ impl Error {
         NotImplementedError::new(message).into()
     }
 
+    /// Returns an error indicating that a command exceeded a consumer's configured rate limit.
+    pub fn rate_limited(message: impl Display) -> Self {
+        RateLimitedError::new(message).into()
+    }
+
     /// Returns an error indicating that the action-target pair is not implemented.
     pub fn not_implemented_pair(action: Action, target: &TargetType) -> Self {
         Self::not_implemented(format!(
@@ -265,6 +271,33 @@ impl fmt::Display for Path {
     }
 }
 
+impl Path {
+    /// Renders this path as an RFC 6901 JSON Pointer, e.g. `/args/3/drop_process`.
+    ///
+    /// `~` and `/` in key segments are escaped as `~0` and `~1` respectively, and
+    /// number segments are rendered as their decimal index.
+    pub fn to_json_pointer(&self) -> String {
+        let mut pointer = String::new();
+        for segment in &self.segments {
+            pointer.push('/');
+            match segment {
+                PathSegment::Key(key) => {
+                    for ch in key.chars() {
+                        match ch {
+                            '~' => pointer.push_str("~0"),
+                            '/' => pointer.push_str("~1"),
+                            _ => pointer.push(ch),
+                        }
+                    }
+                }
+                PathSegment::Number(index) => pointer.push_str(&index.to_string()),
+            }
+        }
+
+        pointer
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, FromVariants)]
 pub enum PathSegment {
     Key(Cow<'static, str>),
@@ -330,6 +363,16 @@ impl ErrorAt for ValidationError {
     }
 }
 
+impl Serialize for ValidationError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(3))?;
+        map.serialize_entry("pointer", &self.path.to_json_pointer())?;
+        map.serialize_entry("message", &self.message)?;
+        map.serialize_entry("kind", "validation")?;
+        map.end()
+    }
+}
+
 /// Error indicating that a consumer does not implement a requested feature.
 #[derive(Debug, Clone, thiserror::Error)]
 pub struct NotImplementedError {
@@ -370,6 +413,34 @@ impl fmt::Display for NotImplementedError {
     }
 }
 
+impl Serialize for NotImplementedError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(3))?;
+        map.serialize_entry(
+            "pointer",
+            &self.path.as_ref().map_or_else(String::new, Path::to_json_pointer),
+        )?;
+        map.serialize_entry("message", &self.message)?;
+        map.serialize_entry("kind", "not_implemented")?;
+        map.end()
+    }
+}
+
+/// Error indicating that a command exceeded a consumer's configured rate limit.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("{message}")]
+pub struct RateLimitedError {
+    message: String,
+}
+
+impl RateLimitedError {
+    pub fn new(message: impl Display) -> Self {
+        Self {
+            message: message.to_string(),
+        }
+    }
+}
+
 impl From<ValidationError> for Error {
     fn from(err: ValidationError) -> Self {
         Self {
@@ -386,6 +457,14 @@ impl From<NotImplementedError> for Error {
     }
 }
 
+impl From<RateLimitedError> for Error {
+    fn from(err: RateLimitedError) -> Self {
+        Self {
+            kind: ErrorKind::RateLimited(err),
+        }
+    }
+}
+
 #[cfg(feature = "json")]
 impl From<serde_json::Error> for Error {
     fn from(err: serde_json::Error) -> Self {
@@ -412,6 +491,8 @@ enum ErrorKind {
     #[error("{0}")]
     NotImplemented(NotImplementedError),
     #[error("{0}")]
+    RateLimited(RateLimitedError),
+    #[error("{0}")]
     Custom(String),
     #[cfg(feature = "json")]
     #[error("JSON error: {0}")]
@@ -423,11 +504,50 @@ enum ErrorKind {
     Multiple(Vec<Error>),
 }
 
+impl Serialize for Error {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match &self.kind {
+            ErrorKind::Validation(err) => err.serialize(serializer),
+            ErrorKind::NotImplemented(err) => err.serialize(serializer),
+            ErrorKind::RateLimited(err) => {
+                let mut map = serializer.serialize_map(Some(2))?;
+                map.serialize_entry("message", &err.message)?;
+                map.serialize_entry("kind", "rate_limited")?;
+                map.end()
+            }
+            ErrorKind::Custom(message) => {
+                let mut map = serializer.serialize_map(Some(2))?;
+                map.serialize_entry("message", message)?;
+                map.serialize_entry("kind", "custom")?;
+                map.end()
+            }
+            #[cfg(feature = "json")]
+            ErrorKind::Json(message) => {
+                let mut map = serializer.serialize_map(Some(2))?;
+                map.serialize_entry("message", message)?;
+                map.serialize_entry("kind", "json")?;
+                map.end()
+            }
+            #[cfg(feature = "cbor")]
+            ErrorKind::Cbor(message) => {
+                let mut map = serializer.serialize_map(Some(2))?;
+                map.serialize_entry("message", message)?;
+                map.serialize_entry("kind", "cbor")?;
+                map.end()
+            }
+            // A `Multiple` error has no single pointer/message/kind of its own, so it
+            // serializes as a JSON array of its constituent errors.
+            ErrorKind::Multiple(errors) => errors.serialize(serializer),
+        }
+    }
+}
+
 impl<V> From<Error> for Response<V> {
     fn from(value: Error) -> Self {
         match value.kind {
             ErrorKind::Validation(e) => e.into(),
             ErrorKind::NotImplemented(e) => e.into(),
+            ErrorKind::RateLimited(e) => e.into(),
             ErrorKind::Custom(e) => Self::new(StatusCode::InternalError).with_status_text(e),
             #[cfg(feature = "json")]
             ErrorKind::Json(e) => Self::new(StatusCode::InternalError).with_status_text(e),
@@ -453,3 +573,53 @@ impl<V> From<NotImplementedError> for Response<V> {
         Self::new(StatusCode::NotImplemented).with_status_text(value.to_string())
     }
 }
+
+impl<V> From<RateLimitedError> for Response<V> {
+    fn from(value: RateLimitedError) -> Self {
+        // OpenC2 defines no dedicated "too many requests" status; 429 is the closest widely
+        // understood analog and round-trips via `StatusCode::Unknown`.
+        Self::new(StatusCode::Unknown(429)).with_status_text(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, Path, PathSegment, ValidationError};
+
+    #[test]
+    fn json_pointer_escapes_key_segments() {
+        let mut path = Path::default();
+        path.push_front(PathSegment::Number(3));
+        path.push_front(PathSegment::Key("drop_process".into()));
+        path.push_front(PathSegment::Key("a/b~c".into()));
+
+        assert_eq!(path.to_json_pointer(), "/a~1b~0c/drop_process/3");
+    }
+
+    #[test]
+    fn validation_error_serializes_with_pointer_and_kind() {
+        let err = ValidationError::new("must not be empty").at("name");
+        let value = serde_json::to_value(&err).unwrap();
+
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "pointer": "/name",
+                "message": "must not be empty",
+                "kind": "validation",
+            })
+        );
+    }
+
+    #[test]
+    fn multiple_errors_serialize_as_array() {
+        let mut accumulator = Error::accumulator();
+        accumulator.push(ValidationError::new("first").at("a"));
+        accumulator.push(ValidationError::new("second").at("b"));
+        let err = accumulator.checkpoint().unwrap_err();
+
+        let value = serde_json::to_value(&err).unwrap();
+        assert!(value.is_array());
+        assert_eq!(value.as_array().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,122 @@
+//! Length-delimited framing for sending [`Message`](crate::Message)s over a raw byte stream,
+//! such as a TCP socket, instead of an HTTP request/response body.
+//!
+//! A frame is a big-endian `u32` length prefix, followed by that many bytes made up of a
+//! one-byte [`Codec`] tag and the encoded message body.
+
+use std::io;
+
+use futures::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use serde::{Serialize, de::DeserializeOwned};
+
+/// The default ceiling passed to [`read_frame`] when a caller doesn't have a more specific limit
+/// in mind: 16 MiB.
+pub const DEFAULT_MAX_FRAME_SIZE: u32 = 16 * 1024 * 1024;
+
+/// Identifies which wire format a frame's payload is encoded with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+#[repr(u8)]
+pub enum Codec {
+    #[cfg(feature = "json")]
+    Json = 1,
+    #[cfg(feature = "cbor")]
+    Cbor = 2,
+    #[cfg(feature = "msgpack")]
+    MsgPack = 3,
+    #[cfg(feature = "postcard")]
+    Postcard = 4,
+}
+
+impl Codec {
+    fn from_tag(tag: u8) -> io::Result<Self> {
+        match tag {
+            #[cfg(feature = "json")]
+            1 => Ok(Codec::Json),
+            #[cfg(feature = "cbor")]
+            2 => Ok(Codec::Cbor),
+            #[cfg(feature = "msgpack")]
+            3 => Ok(Codec::MsgPack),
+            #[cfg(feature = "postcard")]
+            4 => Ok(Codec::Postcard),
+            other => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("frame uses unknown codec tag {other}"),
+            )),
+        }
+    }
+
+    fn encode<T: Serialize>(self, value: &T) -> io::Result<Vec<u8>> {
+        match self {
+            #[cfg(feature = "json")]
+            Codec::Json => serde_json::to_vec(value).map_err(io::Error::other),
+            #[cfg(feature = "cbor")]
+            Codec::Cbor => serde_cbor::to_vec(value).map_err(io::Error::other),
+            #[cfg(feature = "msgpack")]
+            Codec::MsgPack => rmp_serde::to_vec(value).map_err(io::Error::other),
+            #[cfg(feature = "postcard")]
+            Codec::Postcard => postcard::to_allocvec(value).map_err(io::Error::other),
+        }
+    }
+
+    fn decode<T: DeserializeOwned>(self, bytes: &[u8]) -> io::Result<T> {
+        match self {
+            #[cfg(feature = "json")]
+            Codec::Json => serde_json::from_slice(bytes).map_err(io::Error::other),
+            #[cfg(feature = "cbor")]
+            Codec::Cbor => serde_cbor::from_slice(bytes).map_err(io::Error::other),
+            #[cfg(feature = "msgpack")]
+            Codec::MsgPack => rmp_serde::from_slice(bytes).map_err(io::Error::other),
+            #[cfg(feature = "postcard")]
+            Codec::Postcard => postcard::from_bytes(bytes).map_err(io::Error::other),
+        }
+    }
+}
+
+/// Encodes `value` with `codec` and writes it to `writer` as a single length-delimited frame.
+pub async fn write_frame<W, T>(writer: &mut W, codec: Codec, value: &T) -> io::Result<()>
+where
+    W: AsyncWrite + Unpin,
+    T: Serialize,
+{
+    let payload = codec.encode(value)?;
+    let len = u32::try_from(payload.len() + 1)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "frame body is too large"))?;
+
+    writer.write_all(&len.to_be_bytes()).await?;
+    writer.write_all(&[codec as u8]).await?;
+    writer.write_all(&payload).await?;
+    writer.flush().await
+}
+
+/// Reads a single length-delimited frame from `reader` and decodes it, rejecting frames whose
+/// declared length exceeds `max_size`.
+pub async fn read_frame<R, T>(reader: &mut R, max_size: u32) -> io::Result<T>
+where
+    R: AsyncRead + Unpin,
+    T: DeserializeOwned,
+{
+    let mut len_bytes = [0u8; 4];
+    reader.read_exact(&mut len_bytes).await?;
+    let len = u32::from_be_bytes(len_bytes);
+
+    if len == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "frame is missing its codec tag",
+        ));
+    }
+
+    if len > max_size {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("frame of {len} bytes exceeds the {max_size} byte limit"),
+        ));
+    }
+
+    let mut body = vec![0u8; len as usize];
+    reader.read_exact(&mut body).await?;
+
+    let codec = Codec::from_tag(body[0])?;
+    codec.decode(&body[1..])
+}
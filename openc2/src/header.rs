@@ -24,12 +24,33 @@ impl HeaderName {
     /// Returns a `HeaderName` from a static string.
     ///
     /// # Panics
-    /// This function could panic if the provided string is not a valid header name.
+    /// Panics if `s` is not a valid header name (see [`Self::is_valid`]).
     pub const fn from_static(s: &'static str) -> Self {
+        assert!(Self::is_valid(s), "invalid header name");
         HeaderName {
             inner: Cow::Borrowed(s),
         }
     }
+
+    /// Whether `s` is a valid header name: non-empty, and restricted to ASCII alphanumerics,
+    /// `-`, and `_`, which rules out control characters, whitespace, and anything else that
+    /// wouldn't round-trip cleanly through a wire format.
+    const fn is_valid(s: &str) -> bool {
+        if s.is_empty() {
+            return false;
+        }
+
+        let bytes = s.as_bytes();
+        let mut i = 0;
+        while i < bytes.len() {
+            if !matches!(bytes[i], b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_') {
+                return false;
+            }
+            i += 1;
+        }
+
+        true
+    }
 }
 
 impl fmt::Debug for HeaderName {
@@ -48,6 +69,10 @@ impl FromStr for HeaderName {
     type Err = ParseHeaderNameError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if !Self::is_valid(s) {
+            return Err(ParseHeaderNameError {});
+        }
+
         Ok(HeaderName {
             inner: Cow::Owned(s.to_string()),
         })
@@ -301,4 +326,22 @@ mod tests {
         assert_eq!(headers.len(), 1);
         assert!(headers.contains(TO));
     }
+
+    #[test]
+    fn rejects_empty_name() {
+        assert!("".parse::<HeaderName>().is_err());
+    }
+
+    #[test]
+    fn rejects_whitespace_and_control_characters() {
+        assert!("bad name".parse::<HeaderName>().is_err());
+        assert!("bad\tname".parse::<HeaderName>().is_err());
+        assert!("bad\nname".parse::<HeaderName>().is_err());
+    }
+
+    #[test]
+    fn accepts_token_characters() {
+        assert!("request_id".parse::<HeaderName>().is_ok());
+        assert!("x-custom-header".parse::<HeaderName>().is_ok());
+    }
 }
@@ -7,26 +7,45 @@
 //! This crate helps actuator implementers and other cybersecurity vendors interact with OpenC2 messages.
 
 mod command;
+mod correlator;
 mod data;
+mod dispatch;
 mod error;
+#[cfg(any(
+    feature = "json",
+    feature = "cbor",
+    feature = "msgpack",
+    feature = "postcard"
+))]
+pub mod framing;
 mod message;
+pub mod negotiation;
 mod notification;
 mod profile;
+pub mod rate_limiter;
 mod response;
+#[cfg(all(feature = "signing", feature = "json"))]
+pub mod signing;
 pub mod target;
 mod traits;
 
 pub use error::{Error, ErrorAt};
 
 #[doc(inline)]
-pub use profile::Profile;
+pub use profile::{Profile, ProfileRegistry, Profiles};
 
 #[doc(inline)]
-pub use command::{Action, Args, Command};
+pub use command::{Action, Args, Command, Period, ResolvedPeriod};
+
+#[doc(inline)]
+pub use correlator::{Correlation, Correlator};
 
 #[doc(inline)]
 pub use data::*;
 
+#[doc(inline)]
+pub use dispatch::{CommandHandler, Dispatcher};
+
 #[doc(inline)]
 pub use message::{AsBody, AsContent, Body, Content, Headers, Message};
 
@@ -35,11 +54,20 @@ pub use notification::Notification;
 #[doc(inline)]
 pub use target::{Target, TargetType};
 
+#[doc(inline)]
+pub use rate_limiter::RateLimiter;
+
 #[doc(inline)]
 pub use response::{Response, Results, StatusCode};
 
 pub use traits::{Check, IsEmpty};
 
+/// Derives a [`Check`] impl that walks a struct's fields (or an enum's active variant) and
+/// accumulates every validation error instead of hand-writing the `Accumulator`/`ErrorAt`
+/// boilerplate. See `openc2_derive` for the attributes it supports.
+#[cfg(feature = "derive")]
+pub use openc2_derive::Check;
+
 /// Type aliases for JSON-based OpenC2 messages.
 #[cfg(feature = "json")]
 pub mod json {
@@ -71,3 +99,35 @@ pub mod cbor {
     pub type Results = super::Results<Value>;
     pub type Target = super::Target<Value>;
 }
+
+/// Type aliases for MessagePack-based OpenC2 messages.
+#[cfg(feature = "msgpack")]
+pub mod msgpack {
+    use rmpv::Value;
+
+    pub type Args = super::Args<Value>;
+    pub type Body = super::Body<Content>;
+    pub type Content = super::Content<Value>;
+    pub type Message = super::Message<Headers, Body>;
+    pub type Command = super::Command<Value>;
+    pub type Response = super::Response<Value>;
+    pub type Extensions = super::Extensions<Value>;
+    pub type Results = super::Results<Value>;
+    pub type Target = super::Target<Value>;
+}
+
+/// Type aliases for postcard-based OpenC2 messages.
+#[cfg(feature = "postcard")]
+pub mod postcard {
+    use super::PostcardValue as Value;
+
+    pub type Args = super::Args<Value>;
+    pub type Body = super::Body<Content>;
+    pub type Content = super::Content<Value>;
+    pub type Message = super::Message<Headers, Body>;
+    pub type Command = super::Command<Value>;
+    pub type Response = super::Response<Value>;
+    pub type Extensions = super::Extensions<Value>;
+    pub type Results = super::Results<Value>;
+    pub type Target = super::Target<Value>;
+}
@@ -5,8 +5,8 @@ use serde::{Deserialize, Serialize, Serializer, de::DeserializeOwned};
 use serde_with::skip_serializing_none;
 
 use crate::{
-    Check, Command, CommandId, DateTime, Error, IsEmpty, Notification, Response,
-    error::ValidationError, response::StatusCode,
+    Check, Command, CommandId, DateTime, Error, IsEmpty, Notification, Response, Version,
+    VersionSet, error::ValidationError, response::StatusCode,
 };
 
 #[skip_serializing_none]
@@ -18,6 +18,12 @@ pub struct Headers {
     pub from: Option<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub to: Vec<String>,
+    /// The OpenC2 language/profile versions this sender implements, most-preferred-first.
+    #[serde(default, skip_serializing_if = "VersionSet::is_empty")]
+    pub versions: VersionSet,
+    /// A base64-encoded MAC over the message, set by [`crate::signing::Signer::sign`] and
+    /// checked with [`crate::signing::Verifier`].
+    pub signature: Option<String>,
 }
 
 impl IsEmpty for Headers {
@@ -26,6 +32,8 @@ impl IsEmpty for Headers {
             && self.created.is_none()
             && self.from.is_none()
             && self.to.is_empty()
+            && self.versions.is_empty()
+            && self.signature.is_none()
     }
 }
 
@@ -116,6 +124,9 @@ where
 impl<H, B> Message<H, B> {
     /// The value for [`Message::content_type`] for v1 and v2 of the OpenC2 specification.
     pub const CONTENT_TYPE: &str = "application/openc2";
+
+    /// The OpenC2 language versions this crate understands.
+    pub const SUPPORTED_VERSIONS: &[Version] = &[Version::new(1, 0), Version::new(2, 0)];
 }
 
 impl<V> Message<Headers, Body<Content<V>>> {
@@ -160,6 +171,18 @@ impl<V> Check for Message<Headers, Body<Content<V>>> {
     fn check(&self) -> Result<(), Error> {
         let mut acc = Error::accumulator();
 
+        if !self.headers.versions.is_empty()
+            && self
+                .headers
+                .versions
+                .highest_mutual(&Self::SUPPORTED_VERSIONS.iter().copied().collect())
+                .is_none()
+        {
+            acc.push(
+                ValidationError::new("no mutually supported OpenC2 version").at("versions"),
+            );
+        }
+
         let Body::OpenC2(body) = &self.body;
         match body {
             Content::Request(cmd) => {
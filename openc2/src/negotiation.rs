@@ -0,0 +1,141 @@
+//! Feature negotiation between a producer and a consumer, built on the consumer's advertised
+//! [`Results`] from a "query features" command.
+
+use indexmap::IndexSet;
+
+use crate::{Nsid, RateLimiter, Results, Version};
+
+/// The version a consumer is assumed to support when its advertised [`Results::versions`] is
+/// empty, per the OpenC2 Language Specification.
+pub const DEFAULT_VERSION: Version = Version::new(1, 0);
+
+/// The interaction parameters a producer and consumer agreed on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Negotiated {
+    /// The highest OpenC2 language version both peers support.
+    pub version: Version,
+    /// The profiles both peers support, if the producer requires any.
+    pub profiles: IndexSet<Nsid>,
+    /// The consumer's advertised rate limit, if any.
+    pub rate_limit: Option<u64>,
+}
+
+impl Negotiated {
+    /// Builds a [`RateLimiter`] paced to the consumer's advertised `rate_limit`, or `None` if the
+    /// consumer didn't advertise one.
+    pub fn rate_limiter(&self) -> Option<RateLimiter> {
+        self.rate_limit.map(RateLimiter::new)
+    }
+}
+
+/// An error returned when a producer and consumer have no usable common ground.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum NegotiationError {
+    #[error("no OpenC2 version is supported by both the producer and the consumer")]
+    NoCommonVersion,
+    #[error("none of the producer's required profiles are supported by the consumer")]
+    NoCommonProfile,
+}
+
+/// Negotiates the parameters a producer should use when sending commands to a consumer that
+/// advertised `consumer` in response to a "query features" command.
+///
+/// `producer_versions` and `producer_profiles` are the producer's own capabilities. The agreed
+/// version is the highest value present in both sets, per [`Version`]'s `Ord`. `producer_profiles`
+/// being empty means the producer doesn't require any particular profile, so no profile overlap
+/// is required in that case.
+pub fn negotiate<V>(
+    consumer: &Results<V>,
+    producer_versions: &IndexSet<Version>,
+    producer_profiles: &IndexSet<Nsid>,
+) -> Result<Negotiated, NegotiationError> {
+    let default_versions;
+    let consumer_versions = if consumer.versions.is_empty() {
+        default_versions = IndexSet::from([DEFAULT_VERSION]);
+        &default_versions
+    } else {
+        &consumer.versions
+    };
+
+    let version = producer_versions
+        .intersection(consumer_versions)
+        .max()
+        .copied()
+        .ok_or(NegotiationError::NoCommonVersion)?;
+
+    let profiles = if producer_profiles.is_empty() {
+        IndexSet::new()
+    } else {
+        let common: IndexSet<Nsid> = producer_profiles
+            .intersection(&consumer.profiles)
+            .cloned()
+            .collect();
+        if common.is_empty() {
+            return Err(NegotiationError::NoCommonProfile);
+        }
+        common
+    };
+
+    Ok(Negotiated {
+        version,
+        profiles,
+        rate_limit: consumer.rate_limit,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn results_with(versions: impl IntoIterator<Item = Version>) -> Results<()> {
+        let mut results = Results::default();
+        results.versions = versions.into_iter().collect();
+        results
+    }
+
+    #[test]
+    fn picks_highest_common_version() {
+        let consumer = results_with([Version::new(1, 0), Version::new(2, 0)]);
+        let producer_versions = IndexSet::from([Version::new(2, 0), Version::new(1, 0)]);
+
+        let negotiated =
+            negotiate(&consumer, &producer_versions, &IndexSet::new()).expect("should negotiate");
+        assert_eq!(negotiated.version, Version::new(2, 0));
+    }
+
+    #[test]
+    fn empty_consumer_versions_means_default_only() {
+        let consumer = results_with([]);
+        let producer_versions = IndexSet::from([Version::new(1, 0), Version::new(2, 0)]);
+
+        let negotiated =
+            negotiate(&consumer, &producer_versions, &IndexSet::new()).expect("should negotiate");
+        assert_eq!(negotiated.version, DEFAULT_VERSION);
+    }
+
+    #[test]
+    fn no_common_version_is_an_error() {
+        let consumer = results_with([Version::new(1, 0)]);
+        let producer_versions = IndexSet::from([Version::new(2, 0)]);
+
+        assert_eq!(
+            negotiate(&consumer, &producer_versions, &IndexSet::new()),
+            Err(NegotiationError::NoCommonVersion)
+        );
+    }
+
+    #[test]
+    fn no_common_profile_is_an_error() {
+        let mut consumer = results_with([Version::new(1, 0)]);
+        consumer.profiles = IndexSet::from([Nsid::SLPF]);
+        let producer_versions = IndexSet::from([Version::new(1, 0)]);
+        let producer_profiles = IndexSet::from([Nsid::ER]);
+
+        assert_eq!(
+            negotiate(&consumer, &producer_versions, &producer_profiles),
+            Err(NegotiationError::NoCommonProfile)
+        );
+    }
+}
@@ -1,7 +1,241 @@
-use crate::Nsid;
+//! Support for actuator profiles: namespaced extensions of the base OpenC2 language with their
+//! own strongly-typed target, args, and actuator structs (see `openc2_pf` and `openc2_er`).
+
+use std::{any::Any, collections::HashMap};
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::{Error, Nsid, Target, Value};
 
 /// An OpenC2 actuator profile.
+///
+/// Implemented directly on a profile crate's own target/args/actuator type (e.g.
+/// `openc2_pf::target::Target`), alongside a `TryFrom<Target<V>>` impl that recognizes the
+/// profile's [`Nsid`] and deserializes its payload. Together these let [`Target::resolve`] and
+/// [`ProfileRegistry`] convert a generic [`Target`] into the profile's own type.
 pub trait Profile {
     /// Returns the profile's namespace identifier.
     fn ns() -> &'static Nsid;
 }
+
+type DynResolver<V> =
+    Box<dyn Fn(Target<V>) -> Result<Box<dyn Any + Send + Sync>, Error> + Send + Sync>;
+
+/// A runtime registry of typed [`Profile`] resolvers, indexed by [`Nsid`].
+///
+/// [`Target::resolve`] is the compile-time equivalent for a caller that already knows which
+/// [`Profile`] a target belongs to. `ProfileRegistry` is for the dynamic case, e.g. a consumer
+/// that was built against an open set of profile crates and needs to resolve a target by
+/// whichever namespace it actually carries.
+pub struct ProfileRegistry<V> {
+    resolvers: HashMap<Nsid, DynResolver<V>>,
+}
+
+impl<V> Default for ProfileRegistry<V> {
+    fn default() -> Self {
+        Self {
+            resolvers: HashMap::new(),
+        }
+    }
+}
+
+impl<V: 'static> ProfileRegistry<V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `P` as the type to resolve targets under `P::ns()` into, replacing whatever was
+    /// previously registered for that namespace.
+    pub fn with_profile<P>(mut self) -> Self
+    where
+        P: Profile + TryFrom<Target<V>> + Send + Sync + 'static,
+        P::Error: Into<Error>,
+    {
+        self.resolvers.insert(
+            P::ns().clone(),
+            Box::new(|target: Target<V>| {
+                P::try_from(target)
+                    .map(|typed| Box::new(typed) as Box<dyn Any + Send + Sync>)
+                    .map_err(Into::into)
+            }),
+        );
+        self
+    }
+
+    /// Looks up the resolver registered for `target`'s profile namespace and runs it.
+    ///
+    /// Returns `None` if `target` isn't [`Target::ProfileDefined`] or no resolver is registered
+    /// for its namespace; a caller that already knows which [`Profile`] to expect should use
+    /// [`Target::resolve`] instead, which reports that case as an error.
+    pub fn resolve_dynamic(
+        &self,
+        target: Target<V>,
+    ) -> Option<Result<Box<dyn Any + Send + Sync>, Error>> {
+        let ns = match &target {
+            Target::ProfileDefined(defined) => defined.key.as_ref().clone(),
+            _ => return None,
+        };
+
+        let resolver = self.resolvers.get(&ns)?;
+        Some(resolver(target))
+    }
+}
+
+/// A command scoped to several actuator profile namespaces at once, each carrying its own payload.
+///
+/// Serializes/deserializes as a single map keyed by [`Nsid`], preserving insertion order. Use
+/// [`Self::get`] to pull out and deserialize a single namespace's payload; it works generically
+/// across any wire format's [`Value`] (`json`, `cbor`, ...) instead of being duplicated per
+/// backend.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Profiles<V> {
+    values: IndexMap<Nsid, V>,
+}
+
+impl<V> Default for Profiles<V> {
+    fn default() -> Self {
+        Self {
+            values: IndexMap::new(),
+        }
+    }
+}
+
+impl<V> Profiles<V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a `Profiles` scoped to a single namespace, for the common case of a command that
+    /// only names one actuator profile.
+    pub fn single(ns: Nsid, value: V) -> Self {
+        Self::new().with(ns, value)
+    }
+
+    /// Adds `value` under `ns`, replacing whatever was previously stored for that namespace.
+    pub fn with(mut self, ns: Nsid, value: V) -> Self {
+        self.values.insert(ns, value);
+        self
+    }
+
+    /// The namespaces this command is scoped to, in insertion order.
+    pub fn namespaces(&self) -> impl Iterator<Item = &Nsid> {
+        self.values.keys()
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+impl<V: Value + Clone> Profiles<V> {
+    /// Deserializes the payload stored for `ns` into `U`.
+    ///
+    /// Returns `None` if no payload is stored for that namespace, and `Some(Err(_))` if one is
+    /// stored but doesn't deserialize into `U`.
+    pub fn get<U: DeserializeOwned>(&self, ns: &Nsid) -> Option<Result<U, V::Error>> {
+        self.values.get(ns).cloned().map(V::to_typed)
+    }
+}
+
+impl<V> crate::IsEmpty for Profiles<V> {
+    fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json::Value;
+
+    use crate::{Error, Nsid, Target, Value as _};
+
+    use super::{Profile, ProfileRegistry, Profiles};
+
+    const NS: &Nsid = &Nsid::SLPF;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct SlpfTarget {
+        rule_number: u32,
+    }
+
+    impl Profile for SlpfTarget {
+        fn ns() -> &'static Nsid {
+            NS
+        }
+    }
+
+    impl<V> TryFrom<Target<V>> for SlpfTarget
+    where
+        V: crate::Value + Serialize,
+        Error: From<V::Error>,
+    {
+        type Error = Error;
+
+        fn try_from(value: Target<V>) -> Result<Self, Self::Error> {
+            match value {
+                Target::ProfileDefined(outer) if &outer.key == NS => {
+                    Ok(V::from_typed(&outer.value)?.to_typed()?)
+                }
+                _ => Err(Error::custom("target is not defined by the SLPF profile")),
+            }
+        }
+    }
+
+    fn slpf_target() -> Target<Value> {
+        serde_json::from_value(serde_json::json!({
+            "slpf": {
+                "rule_number": 31
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn resolve_typed() {
+        let resolved: SlpfTarget = slpf_target().resolve().unwrap();
+        assert_eq!(resolved, SlpfTarget { rule_number: 31 });
+    }
+
+    #[test]
+    fn resolve_dynamic() {
+        let registry = ProfileRegistry::new().with_profile::<SlpfTarget>();
+
+        let resolved = registry
+            .resolve_dynamic(slpf_target())
+            .expect("slpf namespace is registered")
+            .expect("payload deserializes into SlpfTarget");
+
+        assert_eq!(
+            resolved.downcast_ref::<SlpfTarget>(),
+            Some(&SlpfTarget { rule_number: 31 })
+        );
+    }
+
+    #[test]
+    fn resolve_dynamic_unregistered_namespace() {
+        let registry = ProfileRegistry::<Value>::new();
+        assert!(registry.resolve_dynamic(slpf_target()).is_none());
+    }
+
+    #[test]
+    fn profiles_preserves_insertion_order_and_gets_typed() {
+        let profiles = Profiles::single(Nsid::SLPF, serde_json::json!({ "rule_number": 31 }))
+            .with(Nsid::ER, serde_json::json!({ "container": "c-1" }));
+
+        assert_eq!(
+            profiles.namespaces().collect::<Vec<_>>(),
+            vec![&Nsid::SLPF, &Nsid::ER]
+        );
+
+        let rule: SlpfTarget = profiles.get(&Nsid::SLPF).unwrap().unwrap();
+        assert_eq!(rule, SlpfTarget { rule_number: 31 });
+        assert!(profiles.get::<SlpfTarget>(&Nsid::SFPF).is_none());
+    }
+}
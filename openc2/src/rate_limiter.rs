@@ -0,0 +1,154 @@
+//! Client-side pacing for a consumer's advertised [`rate_limit`](crate::Results::rate_limit), so a
+//! producer doesn't exceed the consumer's requests-per-minute budget and get throttled with a
+//! [`ServiceUnavailable`](crate::StatusCode::ServiceUnavailable) response.
+
+use std::time::{Duration, Instant};
+
+/// A token-bucket limiter keyed on a consumer's advertised rate limit, expressed in requests per
+/// minute.
+///
+/// The bucket starts full, holds up to `rate_limit` tokens, and refills continuously at
+/// `rate_limit / 60` tokens per second based on elapsed wall-clock time. Each paced command
+/// consumes one token.
+#[derive(Debug)]
+pub struct RateLimiter {
+    capacity: f64,
+    tokens: f64,
+    refill_per_sec: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    /// Creates a limiter that allows up to `rate_limit` requests per minute, starting with a full
+    /// bucket so the first burst isn't penalized.
+    pub fn new(rate_limit: u64) -> Self {
+        let capacity = rate_limit as f64;
+        Self {
+            capacity,
+            tokens: capacity,
+            refill_per_sec: capacity / 60.0,
+            last_refill: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last_refill = now;
+    }
+
+    /// The number of requests still available without waiting, as of now.
+    pub fn remaining(&mut self) -> u64 {
+        self.refill();
+        self.tokens.floor() as u64
+    }
+
+    /// The instant at which another request will become available, or `None` if one is available
+    /// right now.
+    pub fn next_available(&mut self) -> Option<Instant> {
+        self.refill();
+        if self.tokens >= 1.0 || self.refill_per_sec <= 0.0 {
+            return None;
+        }
+
+        let needed = 1.0 - self.tokens;
+        Some(self.last_refill + Duration::from_secs_f64(needed / self.refill_per_sec))
+    }
+
+    /// The configured requests-per-minute budget this limiter was constructed with.
+    pub fn rate_limit(&self) -> u64 {
+        self.capacity as u64
+    }
+
+    /// Attempts to consume one token without waiting.
+    ///
+    /// Returns `true` and consumes a token if one was available, or `false` if the bucket is
+    /// empty. Unlike [`acquire`](Self::acquire), this never blocks - callers that need to reject
+    /// rather than pace an over-budget request should use this.
+    pub fn try_acquire(&mut self) -> bool {
+        self.refill();
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Waits, if necessary, until a slot is available under the budget, then consumes one token.
+    ///
+    /// A `rate_limit` of `0` never refills; callers should check [`remaining`](Self::remaining)
+    /// up front rather than awaiting a limiter that can never grant a slot.
+    pub async fn acquire(&mut self) {
+        loop {
+            self.refill();
+            if self.tokens >= 1.0 {
+                self.tokens -= 1.0;
+                return;
+            }
+
+            let Some(at) = self.next_available() else {
+                return;
+            };
+
+            let delay = at.saturating_duration_since(Instant::now());
+            if !delay.is_zero() {
+                futures_timer::Delay::new(delay).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn starts_full() {
+        let mut limiter = RateLimiter::new(60);
+        assert_eq!(limiter.remaining(), 60);
+    }
+
+    #[test]
+    fn acquiring_consumes_a_token() {
+        let mut limiter = RateLimiter::new(60);
+        assert!(limiter.next_available().is_none());
+        limiter.tokens -= 1.0;
+        assert_eq!(limiter.remaining(), 59);
+    }
+
+    #[test]
+    fn empty_bucket_reports_next_available() {
+        let mut limiter = RateLimiter::new(60);
+        limiter.tokens = 0.0;
+        assert!(limiter.next_available().is_some());
+    }
+
+    #[test]
+    fn zero_rate_limit_never_refills() {
+        let mut limiter = RateLimiter::new(0);
+        assert_eq!(limiter.remaining(), 0);
+        assert!(limiter.next_available().is_none());
+    }
+
+    #[test]
+    fn try_acquire_consumes_a_token_without_waiting() {
+        let mut limiter = RateLimiter::new(60);
+        assert!(limiter.try_acquire());
+        assert_eq!(limiter.remaining(), 59);
+    }
+
+    #[test]
+    fn try_acquire_fails_on_empty_bucket() {
+        let mut limiter = RateLimiter::new(60);
+        limiter.tokens = 0.0;
+        assert!(!limiter.try_acquire());
+    }
+
+    #[test]
+    fn rate_limit_reports_configured_capacity() {
+        let limiter = RateLimiter::new(42);
+        assert_eq!(limiter.rate_limit(), 42);
+    }
+}
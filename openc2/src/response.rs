@@ -1,9 +1,11 @@
 use indexmap::IndexSet;
-use serde::{Deserialize, Serialize};
-use serde_repr::{Deserialize_repr, Serialize_repr};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_with::skip_serializing_none;
 
-use crate::{ActionTargets, Body, Content, Error, Extensions, IsEmpty, Nsid, Value, Version};
+use crate::{
+    ActionTargets, ActionTargetsExt, Body, Command, Content, Error, Extensions, IsEmpty, Nsid,
+    TargetType, UnsupportedPair, Value, Version,
+};
 
 /// A message sent from an entity as the result of a command. Response
 /// messages provide acknowledgement, status, results from a query or other information as requested from
@@ -90,31 +92,64 @@ impl<V> TryFrom<Body<Content<V>>> for Response<V> {
     }
 }
 
-#[derive(
-    Debug, Clone, Copy, Serialize_repr, Deserialize_repr, PartialEq, Eq, Hash, PartialOrd, Ord,
-)]
-#[repr(u16)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
 pub enum StatusCode {
-    Processing = 102,
-    Ok = 200,
-    BadRequest = 400,
-    Unauthorized = 401,
-    Forbidden = 403,
-    NotFound = 404,
-    InternalError = 500,
-    NotImplemented = 501,
-    ServiceUnavailable = 503,
+    Processing,
+    Ok,
+    BadRequest,
+    Unauthorized,
+    Forbidden,
+    NotFound,
+    InternalError,
+    NotImplemented,
+    ServiceUnavailable,
+    /// A status code this crate doesn't have a named variant for, e.g. a vendor extension or a
+    /// code added by a newer version of the spec. Preserves the raw number so it can be
+    /// round-tripped without data loss.
+    Unknown(u16),
 }
 
 impl StatusCode {
+    /// The raw numeric status code, per the OpenC2 Language Specification.
+    pub fn code(&self) -> u16 {
+        match self {
+            StatusCode::Processing => 102,
+            StatusCode::Ok => 200,
+            StatusCode::BadRequest => 400,
+            StatusCode::Unauthorized => 401,
+            StatusCode::Forbidden => 403,
+            StatusCode::NotFound => 404,
+            StatusCode::InternalError => 500,
+            StatusCode::NotImplemented => 501,
+            StatusCode::ServiceUnavailable => 503,
+            StatusCode::Unknown(code) => *code,
+        }
+    }
+
+    fn from_code(code: u16) -> Self {
+        match code {
+            102 => StatusCode::Processing,
+            200 => StatusCode::Ok,
+            400 => StatusCode::BadRequest,
+            401 => StatusCode::Unauthorized,
+            403 => StatusCode::Forbidden,
+            404 => StatusCode::NotFound,
+            500 => StatusCode::InternalError,
+            501 => StatusCode::NotImplemented,
+            503 => StatusCode::ServiceUnavailable,
+            other => StatusCode::Unknown(other),
+        }
+    }
+
     /// Check if status is within 100-199.
     pub fn is_informational(&self) -> bool {
-        matches!(self, StatusCode::Processing)
+        (100..200).contains(&self.code())
     }
 
     /// Check if status is within 200-299.
     pub fn is_success(&self) -> bool {
-        matches!(self, StatusCode::Ok)
+        (200..300).contains(&self.code())
     }
 
     /// Check if status is within 400-599.
@@ -124,21 +159,30 @@ impl StatusCode {
 
     /// Check if status is within 400-499.
     pub fn is_producer_error(&self) -> bool {
-        matches!(
-            self,
-            StatusCode::BadRequest
-                | StatusCode::Unauthorized
-                | StatusCode::Forbidden
-                | StatusCode::NotFound
-        )
+        (400..500).contains(&self.code())
     }
 
     /// Check if status is within 500-599.
     pub fn is_consumer_error(&self) -> bool {
-        matches!(
-            self,
-            StatusCode::InternalError | StatusCode::NotImplemented | StatusCode::ServiceUnavailable
-        )
+        (500..600).contains(&self.code())
+    }
+}
+
+impl Serialize for StatusCode {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_u16(self.code())
+    }
+}
+
+impl<'de> Deserialize<'de> for StatusCode {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Self::from_code(u16::deserialize(deserializer)?))
     }
 }
 
@@ -171,6 +215,27 @@ impl<V: Value> Results<V> {
     }
 }
 
+impl<V> Results<V> {
+    /// Validates `command` against this response's advertised `pairs`, additionally confirming
+    /// that a profile-defined target's profile is among the advertised `profiles`.
+    pub fn validate(&self, command: &Command<V>) -> Result<(), UnsupportedPair> {
+        let unsupported = || UnsupportedPair {
+            action: command.action.clone(),
+            target_type: command.target.kind().to_string(),
+        };
+
+        self.pairs.as_ref().ok_or_else(unsupported)?.validate(command)?;
+
+        if let TargetType::ProfileDefined(profile_target) = command.target.kind()
+            && !self.profiles.contains(profile_target.profile.as_ref())
+        {
+            return Err(unsupported());
+        }
+
+        Ok(())
+    }
+}
+
 impl<V> Default for Results<V> {
     fn default() -> Self {
         Self {
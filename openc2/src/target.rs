@@ -9,7 +9,7 @@ pub use url::Url;
 
 use crate::{
     CommandId, DomainName, EmailAddr, Feature, Hashes, Ipv4Net, Ipv6Net, IsEmpty, MacAddr, Nsid,
-    Payload, Port, error::ValidationError, primitive::Choice,
+    Payload, Port, Profile, error::ValidationError, primitive::Choice,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, FromVariants)]
@@ -49,6 +49,19 @@ impl<V> Target<V> {
             Choice::new(type_name.into(), value),
         ))
     }
+
+    /// Resolves this target into a [`Profile`]'s own strongly-typed equivalent, e.g.
+    /// `openc2_pf::target::Target`, by delegating to `P`'s `TryFrom<Target<V>>` impl.
+    ///
+    /// Returns an error if this isn't a [`Target::ProfileDefined`] for `P::ns()`, or if the
+    /// payload doesn't deserialize into `P`. [`ProfileRegistry`](crate::ProfileRegistry) is the
+    /// dynamic equivalent for a caller that doesn't know which profile to expect at compile time.
+    pub fn resolve<P>(self) -> Result<P, P::Error>
+    where
+        P: Profile + TryFrom<Target<V>>,
+    {
+        P::try_from(self)
+    }
 }
 
 impl<V> From<Vec<Feature>> for Target<V> {
@@ -58,10 +71,8 @@ impl<V> From<Vec<Feature>> for Target<V> {
 }
 
 #[derive(
-    Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord, strum::Display,
+    Debug, Clone, SerializeDisplay, DeserializeFromStr, PartialEq, Eq, Hash, PartialOrd, Ord,
 )]
-#[serde(rename_all = "snake_case")]
-#[strum(serialize_all = "snake_case")]
 pub enum TargetType<'a> {
     Artifact,
     Command,
@@ -77,9 +88,62 @@ pub enum TargetType<'a> {
     MacAddr,
     Process,
     Uri,
-    #[serde(untagged)]
-    #[strum(to_string = "{0}")]
     ProfileDefined(ProfileTargetType<'a>),
+    /// A target type this crate doesn't have a named variant for, e.g. one added by a newer
+    /// version of the spec. Preserves the raw string so it can be round-tripped without loss.
+    Unknown(String),
+}
+
+impl fmt::Display for TargetType<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TargetType::Artifact => write!(f, "artifact"),
+            TargetType::Command => write!(f, "command"),
+            TargetType::File => write!(f, "file"),
+            TargetType::Device => write!(f, "device"),
+            TargetType::DomainName => write!(f, "domain_name"),
+            TargetType::EmailAddr => write!(f, "email_addr"),
+            TargetType::Features => write!(f, "features"),
+            TargetType::Ipv4Net => write!(f, "ipv4_net"),
+            TargetType::Ipv6Net => write!(f, "ipv6_net"),
+            TargetType::Ipv4Connection => write!(f, "ipv4_connection"),
+            TargetType::Ipv6Connection => write!(f, "ipv6_connection"),
+            TargetType::MacAddr => write!(f, "mac_addr"),
+            TargetType::Process => write!(f, "process"),
+            TargetType::Uri => write!(f, "uri"),
+            TargetType::ProfileDefined(target) => write!(f, "{target}"),
+            TargetType::Unknown(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+impl FromStr for TargetType<'_> {
+    type Err = std::convert::Infallible;
+
+    /// Parses a known target type name, a profile-defined `profile/name` pair, or falls back to
+    /// [`Unknown`](TargetType::Unknown) with the raw string preserved - never errors.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "artifact" => TargetType::Artifact,
+            "command" => TargetType::Command,
+            "file" => TargetType::File,
+            "device" => TargetType::Device,
+            "domain_name" => TargetType::DomainName,
+            "email_addr" => TargetType::EmailAddr,
+            "features" => TargetType::Features,
+            "ipv4_net" => TargetType::Ipv4Net,
+            "ipv6_net" => TargetType::Ipv6Net,
+            "ipv4_connection" => TargetType::Ipv4Connection,
+            "ipv6_connection" => TargetType::Ipv6Connection,
+            "mac_addr" => TargetType::MacAddr,
+            "process" => TargetType::Process,
+            "uri" => TargetType::Uri,
+            other => match other.parse::<ProfileTargetType>() {
+                Ok(profile_defined) => TargetType::ProfileDefined(profile_defined),
+                Err(_) => TargetType::Unknown(other.to_string()),
+            },
+        })
+    }
 }
 
 impl<'a, V> From<&'a Target<V>> for TargetType<'a> {
@@ -107,6 +171,33 @@ impl<'a, V> From<&'a Target<V>> for TargetType<'a> {
     }
 }
 
+impl TargetType<'_> {
+    /// Clones any borrowed data so the result no longer depends on the lifetime of whatever
+    /// [`Target`] it was derived from, e.g. before using it as a key in a `'static`-keyed map.
+    pub fn into_owned(self) -> TargetType<'static> {
+        match self {
+            TargetType::Artifact => TargetType::Artifact,
+            TargetType::Command => TargetType::Command,
+            TargetType::File => TargetType::File,
+            TargetType::Device => TargetType::Device,
+            TargetType::DomainName => TargetType::DomainName,
+            TargetType::EmailAddr => TargetType::EmailAddr,
+            TargetType::Features => TargetType::Features,
+            TargetType::Ipv4Net => TargetType::Ipv4Net,
+            TargetType::Ipv6Net => TargetType::Ipv6Net,
+            TargetType::Ipv4Connection => TargetType::Ipv4Connection,
+            TargetType::Ipv6Connection => TargetType::Ipv6Connection,
+            TargetType::MacAddr => TargetType::MacAddr,
+            TargetType::Process => TargetType::Process,
+            TargetType::Uri => TargetType::Uri,
+            TargetType::ProfileDefined(profile_target) => {
+                TargetType::ProfileDefined(profile_target.into_owned())
+            }
+            TargetType::Unknown(name) => TargetType::Unknown(name),
+        }
+    }
+}
+
 /// A target type defined by a profile.
 #[derive(Clone, SerializeDisplay, DeserializeFromStr, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct ProfileTargetType<'a> {
@@ -121,6 +212,14 @@ impl<'a> ProfileTargetType<'a> {
             name: name.into(),
         }
     }
+
+    /// Clones any borrowed fields so the result no longer depends on `'a`.
+    pub fn into_owned(self) -> ProfileTargetType<'static> {
+        ProfileTargetType {
+            profile: Cow::Owned(self.profile.into_owned()),
+            name: Cow::Owned(self.name.into_owned()),
+        }
+    }
 }
 
 impl fmt::Debug for ProfileTargetType<'_> {
@@ -219,14 +318,91 @@ pub struct Ipv6Connection {
     pub protocol: Option<L4Protocol>,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+/// The layer-4 protocol carried by an [`Ipv4Connection`]/[`Ipv6Connection`].
+///
+/// Named variants map to their canonical lowercase OpenC2 names (`"tcp"`, `"udp"`, `"icmp"`,
+/// `"sctp"`, `"icmpv6"`) and to their IANA protocol numbers; any other number round-trips through
+/// [`Other`](L4Protocol::Other) without loss of fidelity.
+#[derive(Debug, Clone, Copy, SerializeDisplay, DeserializeFromStr, PartialEq, Eq, Hash)]
 pub enum L4Protocol {
     Tcp,
     Udp,
     Icmp,
+    Icmpv6,
+    Sctp,
     Other(u8),
 }
 
+impl L4Protocol {
+    const TCP: u8 = 6;
+    const UDP: u8 = 17;
+    const ICMP: u8 = 1;
+    const ICMPV6: u8 = 58;
+    const SCTP: u8 = 132;
+}
+
+impl From<u8> for L4Protocol {
+    fn from(value: u8) -> Self {
+        match value {
+            Self::ICMP => Self::Icmp,
+            Self::TCP => Self::Tcp,
+            Self::UDP => Self::Udp,
+            Self::ICMPV6 => Self::Icmpv6,
+            Self::SCTP => Self::Sctp,
+            other => Self::Other(other),
+        }
+    }
+}
+
+impl From<L4Protocol> for u8 {
+    fn from(value: L4Protocol) -> Self {
+        match value {
+            L4Protocol::Icmp => L4Protocol::ICMP,
+            L4Protocol::Tcp => L4Protocol::TCP,
+            L4Protocol::Udp => L4Protocol::UDP,
+            L4Protocol::Icmpv6 => L4Protocol::ICMPV6,
+            L4Protocol::Sctp => L4Protocol::SCTP,
+            L4Protocol::Other(code) => code,
+        }
+    }
+}
+
+impl fmt::Display for L4Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            L4Protocol::Tcp => f.write_str("tcp"),
+            L4Protocol::Udp => f.write_str("udp"),
+            L4Protocol::Icmp => f.write_str("icmp"),
+            L4Protocol::Icmpv6 => f.write_str("icmpv6"),
+            L4Protocol::Sctp => f.write_str("sctp"),
+            L4Protocol::Other(code) => write!(f, "{code}"),
+        }
+    }
+}
+
+/// Error returned when a string doesn't name a known [`L4Protocol`] or a valid protocol number.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("invalid l4 protocol: {0}")]
+pub struct ParseL4ProtocolError(String);
+
+impl FromStr for L4Protocol {
+    type Err = ParseL4ProtocolError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tcp" => Ok(Self::Tcp),
+            "udp" => Ok(Self::Udp),
+            "icmp" => Ok(Self::Icmp),
+            "icmpv6" => Ok(Self::Icmpv6),
+            "sctp" => Ok(Self::Sctp),
+            other => other
+                .parse::<u8>()
+                .map(Self::from)
+                .map_err(|_| ParseL4ProtocolError(s.to_string())),
+        }
+    }
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Hash)]
 pub struct Process {
@@ -296,4 +472,11 @@ mod tests {
             "er/account"
         );
     }
+
+    #[test]
+    fn unrecognized_target_type_round_trips_through_unknown() {
+        let target_type: TargetType = "process_tree".parse().unwrap();
+        assert_eq!(target_type, TargetType::Unknown("process_tree".to_string()));
+        assert_eq!(target_type.to_string(), "process_tree");
+    }
 }
@@ -0,0 +1,5 @@
+mod check;
+mod is_empty;
+
+pub use check::Check;
+pub use is_empty::IsEmpty;
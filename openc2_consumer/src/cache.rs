@@ -0,0 +1,246 @@
+//! Deduplicates redelivered commands so a consumer is safe to run behind an at-least-once
+//! transport (e.g. [`crate::mqtt`], or any retrying queue) without re-running side effects like
+//! `contain_device` or `delete_file` twice for the same command.
+//!
+//! [`IdempotentConsumer`] wraps any [`Consume`] with a [`CommandCache`]: on a cache hit for a
+//! command-id it replays the stored terminal response instead of calling through, and on a miss
+//! it runs the inner consumer, buffers its stream down to a single terminal response, and stores
+//! that before returning it. Concurrent redeliveries of the same command-id - the usual cause of
+//! a retried command, where the first response was lost in flight rather than never produced -
+//! share one in-flight execution via `future::Shared` instead of each separately re-running the
+//! inner consumer.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use futures::{
+    FutureExt, StreamExt,
+    future::{BoxFuture, Shared},
+    stream::{self, BoxStream},
+};
+use openc2::{
+    CommandId, Headers, StatusCode,
+    json::{Command, Response},
+};
+
+use crate::{Consume, driver};
+
+type Message = openc2::Message<Headers, Command>;
+
+/// A cache of terminal [`Response`]s, keyed by command-id, with TTL-based expiry.
+///
+/// Implemented in-memory by [`InMemoryCache`] here; a Redis-backed implementation is a natural
+/// extension for a consumer that's scaled out across multiple processes (so a redelivery can land
+/// on a different process than the one that handled the original), but isn't provided by this
+/// crate.
+pub trait CommandCache: Send + Sync {
+    /// Returns the cached response for `command_id`, if one is stored and hasn't expired.
+    fn get(&self, command_id: &CommandId) -> Option<Response>;
+
+    /// Stores `response` for `command_id`, to be returned by [`Self::get`] until `ttl` elapses.
+    fn put(&self, command_id: CommandId, response: Response, ttl: Duration);
+}
+
+struct CacheEntry {
+    response: Response,
+    expires_at: Instant,
+}
+
+/// An in-memory [`CommandCache`] backed by a [`HashMap`], suitable for a single-process consumer.
+#[derive(Default)]
+pub struct InMemoryCache {
+    entries: Mutex<HashMap<CommandId, CacheEntry>>,
+}
+
+impl InMemoryCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl CommandCache for InMemoryCache {
+    fn get(&self, command_id: &CommandId) -> Option<Response> {
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.get(command_id)?;
+        if Instant::now() >= entry.expires_at {
+            entries.remove(command_id);
+            return None;
+        }
+        Some(entry.response.clone())
+    }
+
+    fn put(&self, command_id: CommandId, response: Response, ttl: Duration) {
+        self.entries.lock().unwrap().insert(
+            command_id,
+            CacheEntry {
+                response,
+                expires_at: Instant::now() + ttl,
+            },
+        );
+    }
+}
+
+/// Wraps a [`Consume`] with a [`CommandCache`] so a command-id that's already run returns the
+/// stored terminal response instead of re-executing, and concurrent redeliveries of the same
+/// command-id share one in-flight execution instead of racing the inner consumer.
+pub struct IdempotentConsumer<C, B = InMemoryCache> {
+    consumer: Arc<C>,
+    cache: B,
+    ttl: Duration,
+    in_flight: Mutex<HashMap<CommandId, Shared<BoxFuture<'static, Response>>>>,
+}
+
+impl<C> IdempotentConsumer<C, InMemoryCache> {
+    /// Wraps `consumer` with a fresh [`InMemoryCache`], caching terminal responses for `ttl`.
+    pub fn new(consumer: C, ttl: Duration) -> Self {
+        Self::with_cache(consumer, InMemoryCache::new(), ttl)
+    }
+}
+
+impl<C, B: CommandCache> IdempotentConsumer<C, B> {
+    /// Wraps `consumer` with a specific [`CommandCache`] backend, caching terminal responses for
+    /// `ttl`.
+    pub fn with_cache(consumer: C, cache: B, ttl: Duration) -> Self {
+        Self {
+            consumer: Arc::new(consumer),
+            cache,
+            ttl,
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<C, B> Consume for IdempotentConsumer<C, B>
+where
+    C: Consume + Send + Sync + 'static,
+    B: CommandCache,
+{
+    fn consume<'a>(&'a self, msg: Message) -> BoxStream<'a, Response> {
+        let Some(command_id) = driver::effective_command_id(&msg) else {
+            // Nothing to key a cache entry on - fall back to running the inner consumer
+            // uncached, the same as before this wrapper existed.
+            return self.consumer.consume(msg);
+        };
+
+        if let Some(cached) = self.cache.get(&command_id) {
+            return stream::once(async { cached }).boxed();
+        }
+
+        let shared = {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            in_flight
+                .entry(command_id.clone())
+                .or_insert_with(|| {
+                    let consumer = self.consumer.clone();
+                    async move {
+                        consumer
+                            .consume(msg)
+                            .fold(Response::new(StatusCode::Ok), |_, response| async {
+                                response
+                            })
+                            .await
+                    }
+                    .boxed()
+                    .shared()
+                })
+                .clone()
+        };
+
+        stream::once(async move {
+            let response = shared.await;
+            self.cache.put(command_id.clone(), response.clone(), self.ttl);
+            self.in_flight.lock().unwrap().remove(&command_id);
+            response
+        })
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        borrow::Cow,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use openc2::{Action, json::Target, target::Features};
+
+    use super::*;
+    use crate::util::stream_just;
+
+    fn command(request_id: &str) -> Message {
+        Message {
+            headers: Headers {
+                request_id: Some(request_id.to_string()),
+                ..Default::default()
+            },
+            content_type: Cow::Borrowed(Message::CONTENT_TYPE),
+            body: Command::new(Action::Query, Target::Features(Features::new())),
+            status_code: None,
+        }
+    }
+
+    struct CountingConsumer {
+        calls: AtomicUsize,
+    }
+
+    impl Consume for CountingConsumer {
+        fn consume<'a>(&'a self, _msg: Message) -> BoxStream<'a, Response> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            stream_just(Response::new(StatusCode::Ok))
+        }
+    }
+
+    #[test]
+    fn in_memory_cache_expires_entries() {
+        let cache = InMemoryCache::new();
+        let id = "cmd-1".to_string();
+        cache.put(id.clone(), Response::new(StatusCode::Ok), Duration::ZERO);
+        assert!(cache.get(&id).is_none());
+    }
+
+    #[test]
+    fn in_memory_cache_returns_unexpired_entries() {
+        let cache = InMemoryCache::new();
+        let id = "cmd-1".to_string();
+        cache.put(id.clone(), Response::new(StatusCode::Ok), Duration::from_secs(60));
+        assert!(cache.get(&id).is_some());
+    }
+
+    #[test]
+    fn redelivered_command_is_not_re_executed() {
+        futures::executor::block_on(async {
+            let consumer = IdempotentConsumer::new(
+                CountingConsumer {
+                    calls: AtomicUsize::new(0),
+                },
+                Duration::from_secs(60),
+            );
+
+            consumer.consume(command("cmd-1")).collect::<Vec<_>>().await;
+            consumer.consume(command("cmd-1")).collect::<Vec<_>>().await;
+
+            assert_eq!(consumer.consumer.calls.load(Ordering::SeqCst), 1);
+        });
+    }
+
+    #[test]
+    fn distinct_command_ids_both_execute() {
+        futures::executor::block_on(async {
+            let consumer = IdempotentConsumer::new(
+                CountingConsumer {
+                    calls: AtomicUsize::new(0),
+                },
+                Duration::from_secs(60),
+            );
+
+            consumer.consume(command("cmd-1")).collect::<Vec<_>>().await;
+            consumer.consume(command("cmd-2")).collect::<Vec<_>>().await;
+
+            assert_eq!(consumer.consumer.calls.load(Ordering::SeqCst), 2);
+        });
+    }
+}
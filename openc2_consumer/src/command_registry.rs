@@ -0,0 +1,206 @@
+//! Mid-flight status tracking for long-running commands, so a client can re-query a command's
+//! progress after the response stream that originally reported it has ended - the same problem a
+//! connection manager solves by tracking live sessions, or a Debug Adapter Protocol client solves
+//! by tracking outstanding request sequence numbers.
+//!
+//! [`CommandRegistry`] wraps a [`Consume`] and taps its response stream: every [`Response`] a
+//! command produces (e.g. `delete_file`'s `Processing` followed by a terminal `Ok`) is recorded
+//! under that command's id. A `(`[`Action::Query`]`, `[`Target::Command`]`)` command - the OpenC2
+//! base language's own mechanism for asking about a previously issued command - is intercepted and
+//! answered from that record instead of reaching the wrapped consumer. A terminal response's
+//! record expires after a configurable TTL so finished commands are eventually reaped; an
+//! in-progress command's record never expires on its own, since there's no terminal status yet to
+//! start the clock.
+
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use futures::{StreamExt, stream::BoxStream};
+use openc2::{
+    Action, CommandId, Error, Headers, StatusCode,
+    json::{Command, Response, Target},
+};
+
+use crate::{Consume, driver, util::stream_just};
+
+type Message = openc2::Message<Headers, Command>;
+
+struct TrackedStatus {
+    response: Response,
+    /// Set once the command reaches a terminal (non-[`StatusCode::Processing`]) status, so the
+    /// record can be reaped; `None` while the command is still in progress.
+    expires_at: Option<Instant>,
+}
+
+/// Wraps a [`Consume`] to record the latest [`Response`] each command produces, keyed by
+/// command-id, and answers `(Query, Command(id))` lookups from that record.
+pub struct CommandRegistry<C> {
+    consumer: C,
+    statuses: Mutex<HashMap<CommandId, TrackedStatus>>,
+    /// How long a terminal status is kept around before [`Self::status`] treats it as reaped.
+    ttl: Duration,
+}
+
+impl<C> CommandRegistry<C> {
+    /// Wraps `consumer`, keeping a terminal command's status queryable for `ttl` after it
+    /// finishes.
+    pub fn new(consumer: C, ttl: Duration) -> Self {
+        Self {
+            consumer,
+            statuses: Mutex::new(HashMap::new()),
+            ttl,
+        }
+    }
+
+    fn record(&self, command_id: CommandId, response: Response) {
+        let expires_at = (response.status != StatusCode::Processing)
+            .then(|| Instant::now() + self.ttl);
+        self.statuses
+            .lock()
+            .unwrap()
+            .insert(command_id, TrackedStatus { response, expires_at });
+    }
+
+    /// The most recently recorded response for `command_id`, or a [`Error::not_found`] response
+    /// if nothing is tracked (never ran, finished and was reaped, or is owned by another instance
+    /// of this registry).
+    fn status(&self, command_id: &CommandId) -> Response {
+        let mut statuses = self.statuses.lock().unwrap();
+        let expired = matches!(
+            statuses.get(command_id),
+            Some(tracked) if tracked.expires_at.is_some_and(|expires_at| Instant::now() >= expires_at)
+        );
+
+        if expired {
+            statuses.remove(command_id);
+        }
+
+        statuses
+            .get(command_id)
+            .map(|tracked| tracked.response.clone())
+            .unwrap_or_else(|| {
+                Error::not_found(format!("no status recorded for command '{command_id}'")).into()
+            })
+    }
+}
+
+impl<C: Consume + Send + Sync> Consume for CommandRegistry<C> {
+    fn consume<'a>(&'a self, msg: Message) -> BoxStream<'a, Response> {
+        if let (Action::Query, Target::Command(command_id)) = msg.body.as_action_target() {
+            return stream_just(self.status(command_id));
+        }
+
+        let Some(command_id) = driver::effective_command_id(&msg) else {
+            // Nothing to key a status record on - fall back to running the inner consumer
+            // untracked, the same as before this wrapper existed.
+            return self.consumer.consume(msg);
+        };
+
+        self.consumer
+            .consume(msg)
+            .inspect(move |response| self.record(command_id.clone(), response.clone()))
+            .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::*;
+
+    fn command(request_id: &str, action: Action, target: Target) -> Message {
+        Message {
+            headers: Headers {
+                request_id: Some(request_id.to_string()),
+                ..Default::default()
+            },
+            content_type: Cow::Borrowed(Message::CONTENT_TYPE),
+            body: Command::new(action, target),
+            status_code: None,
+        }
+    }
+
+    struct Echo(StatusCode);
+
+    impl Consume for Echo {
+        fn consume<'a>(&'a self, _msg: Message) -> BoxStream<'a, Response> {
+            stream_just(Response::new(self.0))
+        }
+    }
+
+    #[test]
+    fn query_command_returns_not_found_before_anything_runs() {
+        futures::executor::block_on(async {
+            let registry = CommandRegistry::new(Echo(StatusCode::Ok), Duration::from_secs(60));
+
+            let responses: Vec<_> = registry
+                .consume(command(
+                    "q-1",
+                    Action::Query,
+                    Target::Command("cmd-1".to_string()),
+                ))
+                .collect()
+                .await;
+
+            assert_eq!(responses[0].status, StatusCode::NotFound);
+        });
+    }
+
+    #[test]
+    fn query_command_returns_recorded_terminal_status() {
+        futures::executor::block_on(async {
+            let registry = CommandRegistry::new(Echo(StatusCode::Ok), Duration::from_secs(60));
+
+            registry
+                .consume(command(
+                    "cmd-1",
+                    Action::Contain,
+                    Target::Command("cmd-1".to_string()),
+                ))
+                .collect::<Vec<_>>()
+                .await;
+
+            let responses: Vec<_> = registry
+                .consume(command(
+                    "q-1",
+                    Action::Query,
+                    Target::Command("cmd-1".to_string()),
+                ))
+                .collect()
+                .await;
+
+            assert_eq!(responses[0].status, StatusCode::Ok);
+        });
+    }
+
+    #[test]
+    fn expired_terminal_status_is_reaped() {
+        futures::executor::block_on(async {
+            let registry = CommandRegistry::new(Echo(StatusCode::Ok), Duration::ZERO);
+
+            registry
+                .consume(command(
+                    "cmd-1",
+                    Action::Contain,
+                    Target::Command("cmd-1".to_string()),
+                ))
+                .collect::<Vec<_>>()
+                .await;
+
+            let responses: Vec<_> = registry
+                .consume(command(
+                    "q-1",
+                    Action::Query,
+                    Target::Command("cmd-1".to_string()),
+                ))
+                .collect()
+                .await;
+
+            assert_eq!(responses[0].status, StatusCode::NotFound);
+        });
+    }
+}
@@ -0,0 +1,212 @@
+//! Config-driven assembly of a [`SharedRegistry`] so an operator can enable or disable actuator
+//! profiles on a running consumer the way a production deployment manages feature flags.
+//!
+//! [`Registry`]/[`SharedRegistry`] already let a caller reconfigure which consumers are active at
+//! runtime, but deciding *which* profiles should be active still has to happen in code.
+//! [`ConfiguredRegistry`] drives that from an external TOML or JSON [`RegistryConfig`] instead:
+//! every profile in the catalog passed to [`ConfiguredRegistry::from_config`] is registered once,
+//! and the config's per-profile `enabled` flag decides whether it's included in the live
+//! [`SharedRegistry`] snapshot. A command that targets a profile excluded by the config falls
+//! through [`Registry`]'s usual "no consumer matches this pair" path and gets back a
+//! [`NotImplementedError`](openc2::Error::not_implemented)-backed response, the same as if the
+//! profile had never been registered at all.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use openc2::{Error, Nsid};
+use serde::{Deserialize, Serialize};
+
+use crate::registry::{Registration, Registry, SharedRegistry};
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+/// Per-profile settings read from a [`RegistryConfig`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileConfig {
+    /// Whether this profile's consumer is included in the live registry. Defaults to `true` so
+    /// an operator only has to list the profiles they want to turn *off*.
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+    /// Free-form per-profile settings (e.g. a rate limit override), passed through unparsed for
+    /// the profile's own code to interpret.
+    #[serde(flatten, default)]
+    pub settings: BTreeMap<String, String>,
+}
+
+/// The config an operator edits to enable/disable actuator profiles without restarting the
+/// consumer process, keyed by the profile's [`Nsid`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegistryConfig {
+    #[serde(default)]
+    profiles: BTreeMap<Nsid, ProfileConfig>,
+}
+
+impl RegistryConfig {
+    /// Whether `profile` should be active, per this config. A profile absent from the config is
+    /// enabled by default.
+    pub fn is_enabled(&self, profile: &Nsid) -> bool {
+        self.profiles.get(profile).is_none_or(|p| p.enabled)
+    }
+
+    /// The settings configured for `profile`, if it has an entry in this config.
+    pub fn profile(&self, profile: &Nsid) -> Option<&ProfileConfig> {
+        self.profiles.get(profile)
+    }
+
+    /// Reads a config from `path`, parsed as TOML if its extension is `.toml` and JSON otherwise.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| Error::custom(format!("unable to read {}: {e}", path.display())))?;
+
+        if path.extension().is_some_and(|ext| ext == "toml") {
+            toml::from_str(&contents)
+                .map_err(|e| Error::custom(format!("invalid registry config {}: {e}", path.display())))
+        } else {
+            serde_json::from_str(&contents).map_err(Error::from)
+        }
+    }
+}
+
+/// A catalog of per-profile [`Registration`]s whose active subset is driven by a
+/// [`RegistryConfig`], exposed as a hot-swappable [`SharedRegistry`].
+pub struct ConfiguredRegistry {
+    catalog: BTreeMap<Nsid, Arc<Registration>>,
+    live: SharedRegistry,
+}
+
+impl ConfiguredRegistry {
+    /// Registers every profile in `catalog` once, applying `config`'s enable/disable flags to
+    /// decide which are included in the initial [`SharedRegistry`] snapshot.
+    pub fn from_config(
+        catalog: impl IntoIterator<Item = (Nsid, Registration)>,
+        config: &RegistryConfig,
+    ) -> Self {
+        let catalog: BTreeMap<_, _> = catalog
+            .into_iter()
+            .map(|(profile, registration)| (profile, Arc::new(registration)))
+            .collect();
+        let live = SharedRegistry::new(Self::build(&catalog, config));
+
+        Self { catalog, live }
+    }
+
+    fn build(catalog: &BTreeMap<Nsid, Arc<Registration>>, config: &RegistryConfig) -> Registry {
+        let mut registry = Registry::default();
+        for (profile, registration) in catalog {
+            if config.is_enabled(profile) {
+                registry.insert_shared(Arc::clone(registration));
+            }
+        }
+        registry
+    }
+
+    /// The hot-swappable registry reflecting the most recently applied config. Clone this (it's
+    /// cheap - see [`SharedRegistry`]) to hand to a [`drive`](crate::drive)n transport.
+    pub fn shared(&self) -> &SharedRegistry {
+        &self.live
+    }
+
+    /// Re-reads `path`, rebuilds the active subset of `catalog` against it, and publishes the
+    /// result as the new [`SharedRegistry`] snapshot. Registrations already in the catalog are
+    /// reused rather than rebuilt, so in-flight commands aren't affected and no consumer is
+    /// reconstructed just to flip a flag.
+    pub fn reload_from_path(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let config = RegistryConfig::from_path(path)?;
+        self.live.replace(Self::build(&self.catalog, &config));
+        Ok(())
+    }
+}
+
+/// A handle to a background file watcher started by [`ConfiguredRegistry::watch_config`].
+///
+/// Dropping this stops the watch; keep it alive for as long as hot-reloading should stay active.
+#[cfg(feature = "config-watch")]
+pub struct ConfigWatcher {
+    _watcher: notify::RecommendedWatcher,
+}
+
+#[cfg(feature = "config-watch")]
+impl ConfiguredRegistry {
+    /// Watches `path` for changes and calls [`reload_from_path`](Self::reload_from_path)
+    /// whenever it's modified, for as long as the returned [`ConfigWatcher`] is kept alive.
+    ///
+    /// `on_reload_error` is called with any error reloading produces (a malformed config, or a
+    /// file that disappeared mid-write); it doesn't stop the watch, since the previous config
+    /// remains live.
+    pub fn watch_config(
+        self: &Arc<Self>,
+        path: impl AsRef<Path>,
+        mut on_reload_error: impl FnMut(Error) + Send + 'static,
+    ) -> Result<ConfigWatcher, Error> {
+        use notify::Watcher;
+
+        let path: PathBuf = path.as_ref().to_path_buf();
+        let watch_path = path.clone();
+        let this = Arc::clone(self);
+
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            let is_modify = matches!(event, Ok(event) if event.kind.is_modify());
+            if is_modify && let Err(e) = this.reload_from_path(&path) {
+                on_reload_error(e);
+            }
+        })
+        .map_err(|e| Error::custom(format!("unable to start config watcher: {e}")))?;
+
+        watcher
+            .watch(&watch_path, notify::RecursiveMode::NonRecursive)
+            .map_err(|e| {
+                Error::custom(format!("unable to watch {}: {e}", watch_path.display()))
+            })?;
+
+        Ok(ConfigWatcher { _watcher: watcher })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ProfileConfig, RegistryConfig};
+
+    #[test]
+    fn profile_absent_from_config_is_enabled_by_default() {
+        let config = RegistryConfig::default();
+        assert!(config.is_enabled(&openc2::Nsid::SLPF));
+    }
+
+    #[test]
+    fn disabled_profile_is_not_enabled() {
+        let config: RegistryConfig = serde_json::from_str(
+            r#"{ "profiles": { "slpf": { "enabled": false } } }"#,
+        )
+        .unwrap();
+
+        assert!(!config.is_enabled(&openc2::Nsid::SLPF));
+        assert_eq!(
+            config.profile(&openc2::Nsid::SLPF),
+            Some(&ProfileConfig {
+                enabled: false,
+                settings: Default::default(),
+            })
+        );
+    }
+
+    #[test]
+    fn toml_and_json_configs_agree() {
+        let toml_config: RegistryConfig = toml::from_str(
+            "[profiles.slpf]\nenabled = false\nmode = \"strict\"\n",
+        )
+        .unwrap();
+        let json_config: RegistryConfig = serde_json::from_str(
+            r#"{ "profiles": { "slpf": { "enabled": false, "mode": "strict" } } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(toml_config, json_config);
+    }
+}
@@ -0,0 +1,152 @@
+//! A transport-agnostic driver for running a [`Consume`]r against any async I/O loop.
+//!
+//! [`Consume::consume`] is the only integration point the crate defines; wiring it to an actual
+//! socket or event loop means decoding framed commands into [`Message`]s, dispatching them
+//! concurrently, and routing each response back out correlated to the command that produced it.
+//! [`drive`] does exactly that, so callers don't have to hand-roll the concurrency and id-matching
+//! plumbing themselves.
+
+use std::borrow::Cow;
+
+use futures::{Sink, Stream, StreamExt, TryStreamExt, future, stream::BoxStream};
+use openc2::{
+    CommandId, Headers, ResponseType,
+    json::{Command, Response},
+};
+
+use crate::Consume;
+
+type InMessage = openc2::Message<Headers, Command>;
+type OutMessage = openc2::Message<Headers, Response>;
+
+/// The command/request id a response to `msg` should be tagged with, per the same
+/// command_id-falls-back-to-request_id rule [`Message::command_id`](openc2::Message::command_id)
+/// applies to the full `Body<Content<V>>` message shape.
+pub(crate) fn effective_command_id(msg: &InMessage) -> Option<CommandId> {
+    msg.body
+        .command_id
+        .clone()
+        .or_else(|| msg.headers.request_id.clone())
+}
+
+pub(crate) fn tag_response(response: Response, request_id: Option<CommandId>) -> OutMessage {
+    OutMessage {
+        status_code: Some(response.status),
+        headers: Headers {
+            request_id,
+            ..Default::default()
+        },
+        content_type: Cow::Borrowed(OutMessage::CONTENT_TYPE),
+        body: response,
+    }
+}
+
+/// Dispatches one command to `consumer` and returns the (possibly empty) stream of correlated
+/// responses it should produce, per `args.response_requested`.
+pub(crate) fn handle_command<'c, C: Consume + Sync>(
+    consumer: &'c C,
+    msg: InMessage,
+) -> BoxStream<'c, OutMessage> {
+    let request_id = effective_command_id(&msg);
+    let response_requested = msg
+        .body
+        .args
+        .response_requested
+        .unwrap_or(ResponseType::Complete);
+
+    let responses = consumer
+        .consume(msg)
+        .map(move |response| tag_response(response, request_id.clone()));
+
+    match response_requested {
+        // Drain the consumer's stream for its side effects, but emit nothing.
+        ResponseType::None => responses.filter_map(|_| future::ready(None)).boxed(),
+        // Only the first response (e.g. an immediate acknowledgement) is forwarded.
+        ResponseType::Ack => responses.take(1).boxed(),
+        // Every response the consumer produces is forwarded, e.g. a `Processing` update
+        // followed by the final terminal response.
+        ResponseType::Status | ResponseType::Complete => responses.boxed(),
+    }
+}
+
+/// Drives `consumer` from `commands`, writing correlated responses to `responses`, until
+/// `commands` ends or a write to `responses` fails.
+///
+/// Commands are dispatched concurrently and their response streams are interleaved as they
+/// become ready, so a slow command doesn't hold up the response to a faster one that arrived
+/// after it.
+pub async fn drive<C, S, K>(consumer: &C, commands: S, responses: K) -> Result<(), K::Error>
+where
+    C: Consume + Sync,
+    S: Stream<Item = InMessage>,
+    K: Sink<OutMessage>,
+{
+    commands
+        .map(|msg| handle_command(consumer, msg))
+        .flatten_unordered(None)
+        .map(Ok::<_, K::Error>)
+        .forward(responses)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{channel::mpsc, stream};
+    use openc2::{Action, StatusCode, json::Target, target::Features};
+
+    use super::*;
+    use crate::util::stream_just;
+
+    struct Echo;
+
+    impl Consume for Echo {
+        fn consume<'a>(&'a self, _msg: InMessage) -> BoxStream<'a, Response> {
+            stream_just(Response::new(StatusCode::Ok))
+        }
+    }
+
+    fn command(request_id: &str, response_requested: Option<ResponseType>) -> InMessage {
+        let mut body = Command::new(Action::Query, Target::Features(Features::new()));
+        body.args.response_requested = response_requested;
+
+        InMessage {
+            headers: Headers {
+                request_id: Some(request_id.to_string()),
+                ..Default::default()
+            },
+            content_type: Cow::Borrowed(InMessage::CONTENT_TYPE),
+            body,
+            status_code: None,
+        }
+    }
+
+    #[test]
+    fn forwards_one_response_per_command_by_default() {
+        futures::executor::block_on(async {
+            let (tx, rx) = mpsc::unbounded();
+            drive(&Echo, stream::iter([command("1", None)]), tx)
+                .await
+                .unwrap();
+
+            let responses: Vec<_> = rx.collect().await;
+            assert_eq!(responses.len(), 1);
+            assert_eq!(responses[0].headers.request_id, Some("1".to_string()));
+        });
+    }
+
+    #[test]
+    fn response_type_none_emits_nothing() {
+        futures::executor::block_on(async {
+            let (tx, rx) = mpsc::unbounded();
+            drive(
+                &Echo,
+                stream::iter([command("1", Some(ResponseType::None))]),
+                tx,
+            )
+            .await
+            .unwrap();
+
+            assert!(rx.collect::<Vec<_>>().await.is_empty());
+        });
+    }
+}
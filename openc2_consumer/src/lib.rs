@@ -6,10 +6,25 @@ use openc2::{
     json::{Command, Headers, Response},
 };
 
+mod cache;
+mod command_registry;
+#[cfg(feature = "config")]
+pub mod config;
+mod driver;
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
 mod registry;
+mod transport;
 pub mod util;
 
-pub use registry::{BoxConsumer, Registration, Registry, ToRegistration};
+pub use cache::{CommandCache, IdempotentConsumer, InMemoryCache};
+pub use command_registry::CommandRegistry;
+pub use driver::drive;
+pub use registry::{
+    BoxConsumer, ConsumerToken, HighestSeverity, Registration, Registry, ResponseMerge,
+    SharedRegistry, ToRegistration,
+};
+pub use transport::{ChannelTransport, Transport, serve};
 
 use crate::util::stream_just;
 
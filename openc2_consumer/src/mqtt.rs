@@ -0,0 +1,127 @@
+//! MQTT transport binding for driving a [`Consume`]r over a pub/sub broker.
+//!
+//! This is a pub/sub event loop, not a request/response one: commands arrive on a shared command
+//! topic from any producer, get decoded into [`Message`](openc2::Message)s, and are dispatched
+//! through a [`Consume`] implementation - reusing [`driver`](crate::driver)'s per-command
+//! concurrency, `response_requested` filtering, and command-id tagging, so an MQTT-bound consumer
+//! behaves identically to a [`drive`](crate::drive)n one. Each response a consumer produces (a
+//! command may yield more than one - e.g. `Processing` followed by a terminal `Ok`) is published
+//! back as its own message to a topic derived from the command's `headers.from`, the closest thing
+//! the OpenC2 message shape gives a producer to address a reply, as soon as it's ready rather than
+//! waiting for the stream to finish.
+//!
+//! Responses are published at [`DeliveryQuality::AtLeastOnce`] or higher so a disconnect between
+//! the consumer and the broker can't silently drop a response the way "fire and forget" QoS 0
+//! would.
+
+use futures::{StreamExt, stream};
+use openc2::{
+    Headers,
+    json::{Command, Response},
+};
+use rumqttc::{AsyncClient, ClientError, Event, EventLoop, Packet, QoS};
+
+use crate::{Consume, driver};
+
+type InMessage = openc2::Message<Headers, Command>;
+type OutMessage = openc2::Message<Headers, Response>;
+
+/// The delivery guarantee responses are published with.
+///
+/// Mirrors a subset of MQTT's own QoS levels, named for what they mean to a caller rather than
+/// making them memorize the broker's `0`/`1`/`2` numbering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryQuality {
+    /// MQTT QoS 1: the broker acknowledges receipt, and a response may be delivered more than
+    /// once if that acknowledgement is lost.
+    AtLeastOnce,
+    /// MQTT QoS 2: the broker guarantees each response is delivered exactly once, at the cost of
+    /// an extra acknowledgement round trip.
+    ExactlyOnce,
+}
+
+impl From<DeliveryQuality> for QoS {
+    fn from(value: DeliveryQuality) -> Self {
+        match value {
+            DeliveryQuality::AtLeastOnce => QoS::AtLeastOnce,
+            DeliveryQuality::ExactlyOnce => QoS::ExactlyOnce,
+        }
+    }
+}
+
+/// The topic a response to `command` should be published to.
+///
+/// Falls back to `response_topic_prefix` alone if the command doesn't name a sender in
+/// `headers.from` - there's no dedicated reply-to header in the OpenC2 message shape, so `from`
+/// is the closest thing a command gives a consumer to address a reply.
+fn response_topic(response_topic_prefix: &str, command: &InMessage) -> String {
+    match &command.headers.from {
+        Some(from) => format!("{response_topic_prefix}/{from}"),
+        None => response_topic_prefix.to_string(),
+    }
+}
+
+/// Polls `event_loop` for incoming `PUBLISH` packets, decoding each payload as JSON and skipping
+/// anything that isn't a valid [`Message`](openc2::Message) rather than ending the stream - one
+/// malformed command shouldn't take the whole consumer offline. Ends only when the connection
+/// itself fails.
+fn command_stream(event_loop: EventLoop) -> impl futures::Stream<Item = InMessage> {
+    stream::unfold(event_loop, |mut event_loop| async move {
+        loop {
+            match event_loop.poll().await {
+                Ok(Event::Incoming(Packet::Publish(publish))) => {
+                    if let Ok(msg) = serde_json::from_slice::<InMessage>(&publish.payload) {
+                        return Some((msg, event_loop));
+                    }
+                }
+                Ok(_) => {}
+                Err(_) => return None,
+            }
+        }
+    })
+}
+
+/// Dispatches `msg` through `consumer` and pairs every response it produces with the topic it
+/// should be published to, computed once up front since it depends only on the command.
+fn dispatch<'c, C: Consume + Sync>(
+    consumer: &'c C,
+    response_topic_prefix: &'c str,
+    msg: InMessage,
+) -> impl futures::Stream<Item = (String, OutMessage)> + 'c {
+    let topic = response_topic(response_topic_prefix, &msg);
+    driver::handle_command(consumer, msg).map(move |response| (topic.clone(), response))
+}
+
+/// Subscribes to `command_topic` and drives `consumer` against whatever commands arrive on it,
+/// publishing every response to a topic under `response_topic_prefix` (see [`response_topic`]) at
+/// `qos`, until the broker connection ends.
+///
+/// Commands are dispatched concurrently and their response streams interleaved as they become
+/// ready, the same way [`drive`](crate::drive) handles any other transport.
+pub async fn drive_mqtt<C>(
+    consumer: &C,
+    client: AsyncClient,
+    event_loop: EventLoop,
+    command_topic: &str,
+    response_topic_prefix: &str,
+    qos: DeliveryQuality,
+) -> Result<(), ClientError>
+where
+    C: Consume + Sync,
+{
+    let qos = QoS::from(qos);
+    client.subscribe(command_topic, qos).await?;
+
+    let responses = command_stream(event_loop)
+        .map(|msg| dispatch(consumer, response_topic_prefix, msg))
+        .flatten_unordered(None);
+
+    futures::pin_mut!(responses);
+    while let Some((topic, response)) = responses.next().await {
+        let payload =
+            serde_json::to_vec(&response).expect("an OutMessage always serializes to JSON");
+        client.publish(topic, qos, false, payload).await?;
+    }
+
+    Ok(())
+}
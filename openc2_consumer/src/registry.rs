@@ -1,33 +1,178 @@
-use std::collections::{BTreeSet, HashMap, HashSet};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
 
-use async_trait::async_trait;
-use futures::future::join_all;
+use arc_swap::ArcSwap;
+use futures::{
+    FutureExt, StreamExt,
+    future::join_all,
+    stream::{self, BoxStream},
+};
+use indexmap::IndexSet;
 use openc2::{
-    Action, ActionTargets, Error, Feature, Headers, Message, Nsid, ProfileFeatures, StatusCode,
-    TargetType, Value, Version,
+    Action, ActionTargets, Error, Feature, Headers, Nsid, ProfileFeatures, RateLimiter,
+    StatusCode, TargetType, Value, Version,
     json::{Command, Response, Results, Target},
     target::Features,
 };
 
-use crate::Consume;
+use crate::{Consume, util::stream_just};
+
+/// A policy for combining the [`Response`]s from every [`Registration`] that matched the same
+/// `(Action, TargetType)` pair (and profile, if the command specified one) into the single
+/// response a producer receives.
+pub trait ResponseMerge {
+    /// Reduces zero or more matching consumers' responses to one. Called with an empty `Vec`
+    /// only if a command somehow matched a pair with no registrations, which [`Registry`] doesn't
+    /// do in practice.
+    fn merge(&self, responses: Vec<Response>) -> Response;
+}
+
+/// The default [`ResponseMerge`]: picks the highest-severity [`StatusCode`] across all
+/// responses, concatenates distinct `status_text`s, and unions every [`Results`] field - `pairs`
+/// action/target sets are unioned the same way [`Registry::query_features_response`] already
+/// folds them, and `rate_limit` takes the configured maximum advertised value, consistent with
+/// [`Registration::rate_limit`].
+pub struct HighestSeverity;
+
+impl ResponseMerge for HighestSeverity {
+    fn merge(&self, responses: Vec<Response>) -> Response {
+        let mut responses = responses.into_iter();
+        let Some(mut merged) = responses.next() else {
+            return Response::new(StatusCode::Ok);
+        };
+
+        for response in responses {
+            if response.status.code() > merged.status.code() {
+                merged.status = response.status;
+            }
+
+            merged.status_text = match (merged.status_text.take(), response.status_text) {
+                (Some(a), Some(b)) if a != b => Some(format!("{a}; {b}")),
+                (Some(a), None) => Some(a),
+                (_, b) => b,
+            };
+
+            merged.results = match (merged.results.take(), response.results) {
+                (Some(mut a), Some(b)) => {
+                    merge_results(&mut a, b);
+                    Some(a)
+                }
+                (a, b) => a.or(b),
+            };
+        }
+
+        merged
+    }
+}
+
+fn merge_results(acc: &mut Results, other: Results) {
+    acc.versions.extend(other.versions);
+    acc.profiles.extend(other.profiles);
+
+    acc.pairs = match (acc.pairs.take(), other.pairs) {
+        (Some(mut a), Some(b)) => {
+            for (action, targets) in b {
+                a.entry(action).or_default().extend(targets);
+            }
+            Some(a)
+        }
+        (a, b) => a.or(b),
+    };
+
+    acc.rate_limit = match (acc.rate_limit, other.rate_limit) {
+        (Some(a), Some(b)) => Some(a.max(b)),
+        (a, b) => a.or(b),
+    };
+
+    acc.extensions = acc
+        .extensions
+        .clone()
+        .into_iter()
+        .chain(other.extensions)
+        .collect();
+}
 
 pub struct ConsumerToken(usize);
 
+/// A boxed [`Consume`]r, as stored by [`Registration`].
+pub type BoxConsumer = Box<dyn Consume + Send + Sync>;
+
+/// Produces the [`Registration`] metadata - action/target pairs, profile, rate limits - for a
+/// type that also implements [`Consume`], so [`Registry::add`] can register both at once instead
+/// of making the caller duplicate the pairs by hand.
+pub trait ToRegistration {
+    fn to_registration(&self) -> Registration;
+}
+
+impl<T: ToRegistration + ?Sized> ToRegistration for Arc<T> {
+    fn to_registration(&self) -> Registration {
+        (**self).to_registration()
+    }
+}
+
+type Message = openc2::Message<Headers, Command>;
+
+/// The default set of OpenC2 language versions a [`Registry`] negotiates if
+/// [`Registry::with_supported_versions`] is never called.
+fn default_supported_versions() -> IndexSet<Version> {
+    Message::SUPPORTED_VERSIONS.iter().copied().collect()
+}
+
 /// A registration of an OpenC2 consumer along with the action/target pairs it wishes to handle.
 pub struct Registration {
-    consumer: Box<dyn Consume + Send + Sync>,
+    /// The consumer that executes matching commands, or `None` for a registration built to
+    /// describe action/target pairs only (e.g. the aggregate produced by [`From<Registry>`]'s
+    /// plain-data variants, or while [`Registry::add`] is still assembling one).
+    consumer: Option<BoxConsumer>,
     /// A map of the action targets this consumer wishes to handle, keyed by optional profile.
     actions: HashMap<Option<Nsid>, ActionTargets>,
+    /// Per-`(Action, TargetType)` token buckets, so unrelated pairs don't share a budget.
+    rate_limits: HashMap<(Action, TargetType<'static>), Mutex<RateLimiter>>,
 }
 
 impl Registration {
-    pub fn new(consumer: impl Consume + Send + Sync + 'static) -> Self {
+    /// Creates an empty registration with no consumer attached.
+    ///
+    /// Use [`Registry::add`] to register a [`Consume`] + [`ToRegistration`] implementor in one
+    /// step, or attach one by hand with the usual [`with_actions`](Self::with_actions)/
+    /// [`with_rate_limit`](Self::with_rate_limit) builders plus an explicit call to
+    /// [`consume`](Consume::consume) delegation if you're implementing `ToRegistration` yourself.
+    pub fn new() -> Self {
         Self {
-            consumer: Box::new(consumer),
+            consumer: None,
             actions: Default::default(),
+            rate_limits: Default::default(),
         }
     }
 
+    /// Caps the `(action, target_type)` pair to `actions_per_minute` commands per minute,
+    /// enforced with a token bucket in [`consume`](Consume::consume). A command that arrives
+    /// with an empty bucket is rejected with [`Error::rate_limited`] instead of reaching the
+    /// wrapped consumer.
+    pub fn with_rate_limit(
+        mut self,
+        action: Action,
+        target_type: TargetType<'static>,
+        actions_per_minute: u64,
+    ) -> Self {
+        self.rate_limits.insert(
+            (action, target_type),
+            Mutex::new(RateLimiter::new(actions_per_minute)),
+        );
+        self
+    }
+
+    /// The configured maximum rate limit across all of this registration's `(action, target_type)`
+    /// pairs, or `None` if none are configured.
+    fn rate_limit(&self) -> Option<u64> {
+        self.rate_limits
+            .values()
+            .map(|limiter| limiter.lock().unwrap().rate_limit())
+            .max()
+    }
+
     pub fn with_actions(
         mut self,
         actions: impl IntoIterator<Item = (Nsid, Action, TargetType<'static>)>,
@@ -68,7 +213,7 @@ impl Registration {
         self.actions
             .values()
             .flatten()
-            .flat_map(|(a, t)| t.iter().cloned().map(move |target| (*a, target)))
+            .flat_map(|(a, t)| t.iter().cloned().map(move |target| (a.clone(), target)))
     }
 
     /// Checks if this registration matches the given action, target type, and profile.
@@ -83,13 +228,11 @@ impl Registration {
     }
 
     pub fn query_features(&self, features: &Features) -> Result<Response, Error> {
+        let mut results = Results::default();
         if features.contains(&Feature::RateLimit) {
-            return Err(
-                Error::not_implemented("rate limit feature is not implemented").at("features"),
-            );
+            results.rate_limit = self.rate_limit();
         }
 
-        let mut results = Results::default();
         if features.contains(&Feature::Profiles) {
             results.profiles = self.actions.keys().flatten().cloned().collect();
         }
@@ -104,7 +247,7 @@ impl Registration {
                 |mut acc, at| {
                     for (a, t) in &at {
                         for target in t {
-                            acc.entry(*a).or_default().insert(target.clone());
+                            acc.entry(a.clone()).or_default().insert(target.clone());
                         }
                     }
                     acc
@@ -127,22 +270,56 @@ impl Registration {
     }
 }
 
-#[async_trait]
 impl Consume for Registration {
-    async fn consume(&self, msg: Message<Headers, Command>) -> Result<Response, Error> {
+    fn consume<'a>(&'a self, msg: Message) -> BoxStream<'a, Response> {
         if let (Action::Query, Target::Features(features)) = msg.body.as_action_target() {
-            return self.query_features(features);
+            return stream_just(self.query_features(features).into());
         }
 
-        self.consumer.consume(msg).await
+        let action = msg.body.action.clone();
+        let target_type = msg.body.target.kind();
+        if let Some(limiter) = self.rate_limits.get(&(action.clone(), target_type.clone()))
+            && !limiter.lock().unwrap().try_acquire()
+        {
+            return stream_just(
+                Error::rate_limited(format!(
+                    "rate limit exceeded for action '{action}' and target type '{target_type:?}'"
+                ))
+                .into(),
+            );
+        }
+
+        match &self.consumer {
+            Some(consumer) => consumer.consume(msg),
+            None => stream_just(Error::not_implemented("no consumer registered").into()),
+        }
     }
 }
 
 /// An OpenC2 consumer made up of more specific consumers.
-#[derive(Default)]
+///
+/// Each [`Registration`] is kept behind an `Arc` (and the response merge policy behind one too)
+/// so that a whole `Registry` is cheap to clone - the copy-on-write reconfiguration done by
+/// [`SharedRegistry`] relies on this.
+#[derive(Clone)]
 pub struct Registry {
-    consumers: Vec<Option<Registration>>,
+    consumers: Vec<Option<Arc<Registration>>>,
     by_pair: HashMap<(Action, TargetType<'static>), BTreeSet<usize>>,
+    /// The OpenC2 language versions this registry will negotiate with a client, most-preferred-first.
+    supported_versions: IndexSet<Version>,
+    /// The policy used to combine responses when a command matches more than one [`Registration`].
+    merge: Arc<dyn ResponseMerge + Send + Sync>,
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self {
+            consumers: Default::default(),
+            by_pair: Default::default(),
+            supported_versions: default_supported_versions(),
+            merge: Arc::new(HighestSeverity),
+        }
+    }
 }
 
 impl Registry {
@@ -150,8 +327,16 @@ impl Registry {
     ///
     /// Returns a token that can be used to unregister the consumer.
     pub fn insert(&mut self, registration: impl Into<Registration>) -> ConsumerToken {
+        self.insert_shared(Arc::new(registration.into()))
+    }
+
+    /// Registers an already-`Arc`'d [`Registration`], e.g. one shared across repeated rebuilds of
+    /// a [`Registry`] (see `config::ConfiguredRegistry`) so its [`BoxConsumer`] isn't reconstructed
+    /// on every reconfiguration.
+    ///
+    /// Returns a token that can be used to unregister the consumer.
+    pub fn insert_shared(&mut self, registration: Arc<Registration>) -> ConsumerToken {
         let idx = self.consumers.len();
-        let registration = registration.into();
 
         for pair in registration.to_pairs() {
             self.by_pair.entry(pair).or_default().insert(idx);
@@ -162,6 +347,42 @@ impl Registry {
         ConsumerToken(idx)
     }
 
+    /// Registers `consumer`, taking its action/target pairs, profile, and rate limits from its
+    /// own [`ToRegistration::to_registration`] instead of requiring the caller to repeat them.
+    pub fn add<C>(&mut self, consumer: C) -> ConsumerToken
+    where
+        C: Consume + ToRegistration + Send + Sync + 'static,
+    {
+        let mut registration = consumer.to_registration();
+        registration.consumer = Some(Box::new(consumer));
+        self.insert(registration)
+    }
+
+    /// Sets the OpenC2 language versions this registry will negotiate against a client's
+    /// declared [`Headers::versions`], replacing the crate default of
+    /// [`Message::SUPPORTED_VERSIONS`].
+    pub fn with_supported_versions(mut self, versions: impl IntoIterator<Item = Version>) -> Self {
+        self.supported_versions = versions.into_iter().collect();
+        self
+    }
+
+    /// Sets the policy used to combine responses when a command matches more than one
+    /// [`Registration`], replacing the crate default of [`HighestSeverity`].
+    pub fn with_response_merge(mut self, merge: impl ResponseMerge + Send + Sync + 'static) -> Self {
+        self.merge = Arc::new(merge);
+        self
+    }
+
+    /// Picks the highest version shared between `versions` (most-preferred-first) and
+    /// this registry's supported set, or `None` if there's no overlap.
+    fn negotiate_version(&self, versions: &openc2::VersionSet) -> Option<Version> {
+        if versions.is_empty() {
+            return self.supported_versions.first().copied();
+        }
+
+        versions.highest_mutual(&self.supported_versions)
+    }
+
     fn get_matching<'a>(
         &'a self,
         pair: &(Action, TargetType<'a>),
@@ -170,12 +391,12 @@ impl Registry {
         entry.into_iter().flat_map(move |indices| {
             indices
                 .iter()
-                .filter_map(|&idx| self.consumers[idx].as_ref())
+                .filter_map(|&idx| self.consumers[idx].as_deref())
         })
     }
 
     /// Unregister an OpenC2 consumer. This will not drop any in-progress requests.
-    pub fn remove(&mut self, token: ConsumerToken) -> Option<Registration> {
+    pub fn remove(&mut self, token: ConsumerToken) -> Option<Arc<Registration>> {
         let entry = self.consumers.get_mut(token.0)?.take()?;
         for pair in entry.to_pairs() {
             if let Some(set) = self.by_pair.get_mut(&pair) {
@@ -191,7 +412,7 @@ impl Registry {
     pub fn profiles(&self) -> HashSet<&Nsid> {
         self.consumers
             .iter()
-            .filter_map(|c| c.as_ref())
+            .filter_map(|c| c.as_deref())
             .flat_map(|c| c.profiles())
             .collect()
     }
@@ -223,7 +444,7 @@ impl From<Registry> for Registration {
             let profile_entry = actions.entry(profile.clone()).or_default();
             for (action, targets) in acts {
                 profile_entry
-                    .entry(*action)
+                    .entry(action.clone())
                     .or_default()
                     .extend(targets.iter().cloned());
             }
@@ -231,94 +452,221 @@ impl From<Registry> for Registration {
 
         Self {
             actions,
-            consumer: Box::new(value),
+            consumer: Some(Box::new(value)),
+            rate_limits: Default::default(),
         }
     }
 }
 
-#[async_trait]
 impl Consume for Registry {
-    async fn consume(&self, msg: Message<Headers, Command>) -> Result<Response, Error> {
+    fn consume<'a>(&'a self, msg: Message) -> BoxStream<'a, Response> {
+        if let Some(version) = self.negotiate_version(&msg.headers.versions) {
+            return self.consume_negotiated(msg, version);
+        }
+
+        stream_just(
+            Error::validation(format!(
+                "no OpenC2 version in {:?} is supported by this registry",
+                msg.headers.versions
+            ))
+            .into(),
+        )
+    }
+}
+
+impl Registry {
+    fn consume_negotiated<'a>(&'a self, msg: Message, _version: Version) -> BoxStream<'a, Response> {
         if msg.body.action == Action::Query
             && let Target::Features(features) = &msg.body.target
         {
-            if features.contains(&Feature::RateLimit) {
-                return Err(
-                    Error::not_implemented("rate limit feature is not implemented").at("features"),
-                );
-            }
-
-            let mut results = Results::default();
-            if features.contains(&Feature::Profiles) {
-                results.profiles = self.profiles().into_iter().cloned().collect();
-            }
-
-            if features.contains(&Feature::Versions) {
-                results.versions = [Version::new(2, 0)].into_iter().collect();
-            }
-
-            if features.contains(&Feature::Pairs) {
-                results.pairs = Some(self.pairs());
-
-                let mut profiles: HashMap<_, ActionTargets> = HashMap::new();
-                for consumer in self.consumers.iter().flatten() {
-                    for (profile, actions) in &consumer.actions {
-                        let Some(profile) = profile else {
-                            continue;
-                        };
-                        let profile_entry = profiles.entry(profile.clone()).or_default();
-                        for (action, target) in actions {
-                            profile_entry
-                                .entry(*action)
-                                .or_default()
-                                .extend(target.clone());
-                        }
-                    }
-                }
-
-                results = results
-                    .with_extensions(
-                        profiles
-                            .into_iter()
-                            .map(|(ap, pairs)| (ap, ProfileFeatures { pairs })),
-                    )
-                    .map_err(|e| {
-                        Error::custom(format!("unable to serialize profile-specific pairs: {e}"))
-                    })?;
-            }
-
-            return Ok(Response::new(StatusCode::Ok).with_results(results));
+            return stream_just(self.query_features_response(features).into());
         }
 
-        let action = msg.body.action;
+        let action = msg.body.action.clone();
         let target_type = msg.body.target.kind();
         let mut consumers = self
-            .get_matching(&(action, target_type.clone()))
+            .get_matching(&(action.clone(), target_type.clone()))
             .collect::<Vec<_>>();
 
         if consumers.is_empty() {
-            return Err(Error::not_implemented_pair(action, &target_type));
+            return stream_just(Error::not_implemented_pair(action, &target_type).into());
         }
 
         if let Some(profile) = &msg.body.profile {
-            consumers.retain(|consumer| consumer.matches(action, &target_type, profile));
+            consumers.retain(|consumer| consumer.matches(action.clone(), &target_type, profile));
         }
 
         if consumers.is_empty() {
-            return Err(Error::not_implemented(format!(
-                "No consumer for action '{action}' and target type '{target_type:?}' matches profile '{:?}'",
-                msg.body.profile
-            )));
+            return stream_just(
+                Error::not_implemented(format!(
+                    "No consumer for action '{action}' and target type '{target_type:?}' matches profile '{:?}'",
+                    msg.body.profile
+                ))
+                .into(),
+            );
         }
 
-        let futures = consumers
-            .into_iter()
-            .map(|consumer| consumer.consume(msg.clone()));
-        let results: Vec<Result<Response, Error>> = join_all(futures).await;
-        // TODO figure out how to combine multiple responses
-        return results
-            .into_iter()
-            .next()
-            .expect("at least one consumer exists");
+        if consumers.len() == 1 {
+            return consumers[0].consume(msg);
+        }
+
+        let merge = &self.merge;
+        let responses = join_all(consumers.iter().map(|c| c.consume(msg.clone()).collect()))
+            .map(|responses: Vec<Vec<Response>>| {
+                merge.merge(responses.into_iter().flatten().collect())
+            });
+
+        stream::once(responses).boxed()
+    }
+
+    fn query_features_response(&self, features: &Features) -> Result<Response, Error> {
+        let mut results = Results::default();
+        if features.contains(&Feature::RateLimit) {
+            results.rate_limit = self
+                .consumers
+                .iter()
+                .flatten()
+                .filter_map(|c| c.rate_limit())
+                .max();
+        }
+
+        if features.contains(&Feature::Profiles) {
+            results.profiles = self.profiles().into_iter().cloned().collect();
+        }
+
+        if features.contains(&Feature::Versions) {
+            results.versions = self.supported_versions.iter().copied().collect();
+        }
+
+        if features.contains(&Feature::Pairs) {
+            results.pairs = Some(self.pairs());
+
+            let mut profiles: HashMap<_, ActionTargets> = HashMap::new();
+            for consumer in self.consumers.iter().flatten() {
+                for (profile, actions) in &consumer.actions {
+                    let Some(profile) = profile else {
+                        continue;
+                    };
+                    let profile_entry = profiles.entry(profile.clone()).or_default();
+                    for (action, target) in actions {
+                        profile_entry
+                            .entry(action.clone())
+                            .or_default()
+                            .extend(target.clone());
+                    }
+                }
+            }
+
+            results = results
+                .with_extensions(
+                    profiles
+                        .into_iter()
+                        .map(|(ap, pairs)| (ap, ProfileFeatures { pairs })),
+                )
+                .map_err(|e| {
+                    Error::custom(format!("unable to serialize profile-specific pairs: {e}"))
+                })?;
+        }
+
+        Ok(Response::new(StatusCode::Ok).with_results(results))
+    }
+}
+
+/// A thread-safe, hot-swappable front-end for [`Registry`].
+///
+/// [`Registry::insert`]/[`Registry::remove`] require `&mut self`, which forces callers to take
+/// exclusive ownership (behind a lock) to reconfigure which consumers are registered - blocking
+/// every in-flight [`Consume::consume`] call in the process. `SharedRegistry` instead holds the
+/// active [`Registry`] behind an atomically-swapped pointer (see the `arc-swap` crate): a
+/// reconfiguration clones the current registry (cheap, since every [`Registration`] is kept
+/// behind an `Arc`), mutates the clone, and publishes it with a single atomic store. A `consume`
+/// call already in flight keeps running against the snapshot it captured when it started, so a
+/// long-running consumer service can add or drop profiles without dropping or deadlocking active
+/// commands.
+///
+/// Writers (`insert`/`remove`/`reload`) are serialized against each other by an internal lock;
+/// readers never take it and are never blocked by a writer.
+pub struct SharedRegistry {
+    current: ArcSwap<Registry>,
+    /// Serializes reconfiguration so two concurrent writers can't race on a stale snapshot.
+    write_lock: Mutex<()>,
+}
+
+impl Default for SharedRegistry {
+    fn default() -> Self {
+        Self::new(Registry::default())
+    }
+}
+
+impl SharedRegistry {
+    /// Wraps an existing [`Registry`] for concurrent, hot-swappable access.
+    pub fn new(registry: Registry) -> Self {
+        Self {
+            current: ArcSwap::from_pointee(registry),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    /// Returns an `Arc` snapshot of the currently active [`Registry`].
+    ///
+    /// The returned snapshot is immutable and unaffected by any later reconfiguration - it's
+    /// exactly what a long-running `consume` call holds on to while it executes.
+    pub fn snapshot(&self) -> Arc<Registry> {
+        self.current.load_full()
+    }
+
+    /// Registers a consumer, publishing a new snapshot that includes it.
+    ///
+    /// The returned [`ConsumerToken`] remains valid for a later [`remove`](Self::remove) across
+    /// any number of other `insert`/`remove` calls, because removal only clears a slot rather
+    /// than reindexing the rest. A wholesale [`reload`](Self::reload), however, discards the
+    /// whole index space and invalidates every token issued before it.
+    pub fn insert(&self, registration: impl Into<Registration>) -> ConsumerToken {
+        let _guard = self.write_lock.lock().unwrap();
+        let mut next = (*self.current.load_full()).clone();
+        let token = next.insert(registration);
+        self.current.store(Arc::new(next));
+        token
+    }
+
+    /// Unregisters a consumer, publishing a new snapshot without it. This will not drop any
+    /// in-progress requests, which keep running against the snapshot they started with.
+    pub fn remove(&self, token: ConsumerToken) -> Option<Arc<Registration>> {
+        let _guard = self.write_lock.lock().unwrap();
+        let mut next = (*self.current.load_full()).clone();
+        let removed = next.remove(token);
+        self.current.store(Arc::new(next));
+        removed
+    }
+
+    /// Atomically replaces the entire set of registered consumers.
+    ///
+    /// This is a single pointer swap from a reader's perspective: any `consume` already running
+    /// keeps using the registry it started with, and the next `consume` call sees the new set.
+    pub fn reload(&self, registrations: impl IntoIterator<Item = Registration>) {
+        self.replace(registrations.into_iter().collect());
+    }
+
+    /// Atomically publishes an already-built [`Registry`] as the active snapshot.
+    ///
+    /// Unlike [`reload`](Self::reload), this doesn't require owning each [`Registration`]
+    /// outright, so a caller that keeps its registrations behind `Arc` (e.g.
+    /// `config::ConfiguredRegistry`, rebuilding the same catalog under a new config) can swap in a
+    /// new [`Registry`] without reconstructing every [`BoxConsumer`].
+    pub fn replace(&self, registry: Registry) {
+        let _guard = self.write_lock.lock().unwrap();
+        self.current.store(Arc::new(registry));
+    }
+}
+
+impl Consume for SharedRegistry {
+    fn consume<'a>(&'a self, msg: Message) -> BoxStream<'a, Response> {
+        let snapshot = self.snapshot();
+        stream::once(async move {
+            let responses: Vec<Response> = snapshot.consume(msg).collect().await;
+            stream::iter(responses)
+        })
+        .flatten()
+        .boxed()
     }
 }
@@ -0,0 +1,140 @@
+//! A transport-agnostic server loop for driving a [`Consume`]r from an async command source.
+//!
+//! [`drive`](crate::drive) and [`drive_mqtt`](crate::mqtt::drive_mqtt) each wire a [`Consume`]r up
+//! to a specific shape of I/O (a `Stream`/`Sink` pair, an MQTT broker). [`Transport`] abstracts
+//! that down to the two operations any integration needs - receive a command, send a response -
+//! so [`serve`] can drive a consumer from whatever implements it without reimplementing the
+//! per-command concurrency and `response_requested` handling [`driver::handle_command`] already
+//! provides.
+
+use futures::{FutureExt, SinkExt, StreamExt, channel::mpsc, future::BoxFuture};
+use openc2::{
+    Headers,
+    json::{Command, Response},
+};
+
+use crate::{Consume, driver};
+
+type InMessage = openc2::Message<Headers, Command>;
+type OutMessage = openc2::Message<Headers, Response>;
+
+/// A transport a [`Consume`]r can be [`serve`]d over.
+pub trait Transport: Send {
+    /// Receives the next command, or `None` once the transport has closed.
+    fn recv(&mut self) -> BoxFuture<'_, Option<InMessage>>;
+
+    /// Sends a response back over the transport.
+    fn send(&mut self, response: OutMessage) -> BoxFuture<'_, ()>;
+}
+
+/// Runs `consumer` against every command `transport` produces, forwarding its responses back
+/// through the same transport, until `transport` closes.
+///
+/// Each command is dispatched through [`driver::handle_command`], so `args.response_requested`
+/// decides how many responses are sent for it (none, one, or every one the consumer produces)
+/// and each response is tagged with the command's id - the same behavior [`drive`](crate::drive)
+/// and [`drive_mqtt`](crate::mqtt::drive_mqtt) give a `Stream`/`Sink`- or MQTT-bound consumer.
+pub async fn serve<C, T>(consumer: &C, mut transport: T)
+where
+    C: Consume + Sync,
+    T: Transport,
+{
+    while let Some(msg) = transport.recv().await {
+        let mut responses = driver::handle_command(consumer, msg);
+        while let Some(response) = responses.next().await {
+            transport.send(response).await;
+        }
+    }
+}
+
+/// A [`Transport`] backed by [`futures::channel::mpsc`] channels, for driving [`serve`] in tests
+/// without a real transport.
+pub struct ChannelTransport {
+    commands: mpsc::UnboundedReceiver<InMessage>,
+    responses: mpsc::UnboundedSender<OutMessage>,
+}
+
+impl ChannelTransport {
+    /// Creates a `ChannelTransport` along with the sender/receiver a caller uses to drive it:
+    /// push commands into the returned sender, and drain responses from the returned receiver.
+    pub fn new() -> (
+        Self,
+        mpsc::UnboundedSender<InMessage>,
+        mpsc::UnboundedReceiver<OutMessage>,
+    ) {
+        let (commands_tx, commands_rx) = mpsc::unbounded();
+        let (responses_tx, responses_rx) = mpsc::unbounded();
+
+        (
+            Self {
+                commands: commands_rx,
+                responses: responses_tx,
+            },
+            commands_tx,
+            responses_rx,
+        )
+    }
+}
+
+impl Transport for ChannelTransport {
+    fn recv(&mut self) -> BoxFuture<'_, Option<InMessage>> {
+        self.commands.next().boxed()
+    }
+
+    fn send(&mut self, response: OutMessage) -> BoxFuture<'_, ()> {
+        let mut responses = self.responses.clone();
+        async move {
+            // The receiving end having hung up just means nobody's listening for responses
+            // anymore; `serve` should keep running rather than treat it as fatal.
+            let _ = responses.send(response).await;
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openc2::{Action, StatusCode, json::Target, target::Features};
+
+    use super::*;
+    use crate::util::stream_just;
+    use futures::stream::BoxStream;
+
+    struct Echo;
+
+    impl Consume for Echo {
+        fn consume<'a>(&'a self, _msg: InMessage) -> BoxStream<'a, Response> {
+            stream_just(Response::new(StatusCode::Ok))
+        }
+    }
+
+    fn command(request_id: &str) -> InMessage {
+        let body = Command::new(Action::Query, Target::Features(Features::new()));
+
+        InMessage {
+            headers: Headers {
+                request_id: Some(request_id.to_string()),
+                ..Default::default()
+            },
+            content_type: std::borrow::Cow::Borrowed(InMessage::CONTENT_TYPE),
+            body,
+            status_code: None,
+        }
+    }
+
+    #[test]
+    fn serve_forwards_responses_tagged_with_request_id() {
+        futures::executor::block_on(async {
+            let (transport, mut commands_tx, mut responses_rx) = ChannelTransport::new();
+
+            commands_tx.send(command("1")).await.unwrap();
+            drop(commands_tx);
+
+            serve(&Echo, transport).await;
+
+            let response = responses_rx.next().await.unwrap();
+            assert_eq!(response.headers.request_id, Some("1".to_string()));
+            assert!(responses_rx.next().await.is_none());
+        });
+    }
+}
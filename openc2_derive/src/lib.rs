@@ -0,0 +1,187 @@
+//! `#[derive(Check)]` for the `openc2` crate's `Check` trait.
+//!
+//! The derive generates the same `Error::accumulator()` / `.at(..)` boilerplate contributors
+//! already hand-write for `Check` impls in this workspace: for a struct it calls `check()` on
+//! every field and records errors under that field's name; for an enum it dispatches to the
+//! active variant and records errors under each of its fields. A field can opt out of this
+//! default with `#[check(skip)]`, or plug in a custom validator with
+//! `#[check(with = "path::to::fn")]`, where `fn` takes a reference to the field and returns
+//! `Result<(), E>` for some `E: Into<Error>`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Fields, Index, parse_macro_input};
+
+#[proc_macro_derive(Check, attributes(check))]
+pub fn derive_check(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let body = match &input.data {
+        Data::Struct(data) => struct_body(&data.fields),
+        Data::Enum(data) => enum_body(data),
+        Data::Union(_) => {
+            return syn::Error::new(name.span(), "#[derive(Check)] does not support unions")
+                .to_compile_error()
+                .into();
+        }
+    };
+
+    quote! {
+        impl #impl_generics ::openc2::Check for #name #ty_generics #where_clause {
+            fn check(&self) -> ::core::result::Result<(), ::openc2::Error> {
+                #body
+            }
+        }
+    }
+    .into()
+}
+
+/// How a single field participates in the generated `check()`.
+enum FieldCheck {
+    /// Call `Check::check()` on the field (the default).
+    Default,
+    /// Skip the field entirely; its type need not implement `Check`.
+    Skip,
+    /// Call the named function with a reference to the field instead of `Check::check()`.
+    With(syn::Path),
+}
+
+fn parse_field_check(attrs: &[syn::Attribute]) -> FieldCheck {
+    let mut result = FieldCheck::Default;
+
+    for attr in attrs {
+        if !attr.path().is_ident("check") {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                result = FieldCheck::Skip;
+                Ok(())
+            } else if meta.path.is_ident("with") {
+                let path: syn::LitStr = meta.value()?.parse()?;
+                result = FieldCheck::With(path.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `skip` or `with = \"path::to::fn\"`"))
+            }
+        })
+        .expect("invalid #[check(..)] attribute");
+    }
+
+    result
+}
+
+/// Emits the accumulator statement for one field, given an expression that evaluates to a
+/// reference to the field and the `PathSegment` it should be reported under.
+fn check_stmt(check: &FieldCheck, field_ref: TokenStream2, path_segment: TokenStream2) -> TokenStream2 {
+    match check {
+        FieldCheck::Skip => quote! {},
+        FieldCheck::With(path) => quote! {
+            if let ::core::result::Result::Err(e) = #path(#field_ref) {
+                acc.push(::openc2::ErrorAt::at(::core::convert::Into::into(e), #path_segment));
+            }
+        },
+        FieldCheck::Default => quote! {
+            if let ::core::result::Result::Err(e) = ::openc2::Check::check(#field_ref) {
+                acc.push(::openc2::ErrorAt::at(e, #path_segment));
+            }
+        },
+    }
+}
+
+fn struct_body(fields: &Fields) -> TokenStream2 {
+    let stmts: Vec<TokenStream2> = match fields {
+        Fields::Named(fields) => fields
+            .named
+            .iter()
+            .map(|field| {
+                let ident = field.ident.as_ref().expect("named field has an ident");
+                let name = ident.to_string();
+                check_stmt(
+                    &parse_field_check(&field.attrs),
+                    quote! { &self.#ident },
+                    quote! { #name },
+                )
+            })
+            .collect(),
+        Fields::Unnamed(fields) => fields
+            .unnamed
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let index = Index::from(i);
+                check_stmt(
+                    &parse_field_check(&field.attrs),
+                    quote! { &self.#index },
+                    quote! { #i },
+                )
+            })
+            .collect(),
+        Fields::Unit => Vec::new(),
+    };
+
+    quote! {
+        let mut acc = ::openc2::Error::accumulator();
+        #(#stmts)*
+        acc.finish()
+    }
+}
+
+fn enum_body(data: &syn::DataEnum) -> TokenStream2 {
+    let arms = data.variants.iter().map(|variant| {
+        let variant_ident = &variant.ident;
+
+        match &variant.fields {
+            Fields::Named(fields) => {
+                let idents: Vec<_> = fields
+                    .named
+                    .iter()
+                    .map(|field| field.ident.clone().expect("named field has an ident"))
+                    .collect();
+                let stmts: Vec<TokenStream2> = fields
+                    .named
+                    .iter()
+                    .zip(&idents)
+                    .map(|(field, ident)| {
+                        let name = ident.to_string();
+                        check_stmt(&parse_field_check(&field.attrs), quote! { #ident }, quote! { #name })
+                    })
+                    .collect();
+
+                quote! {
+                    Self::#variant_ident { #(#idents),* } => { #(#stmts)* }
+                }
+            }
+            Fields::Unnamed(fields) => {
+                let idents: Vec<_> = (0..fields.unnamed.len())
+                    .map(|i| format_ident!("field_{i}"))
+                    .collect();
+                let stmts: Vec<TokenStream2> = fields
+                    .unnamed
+                    .iter()
+                    .zip(idents.iter().enumerate())
+                    .map(|(field, (i, ident))| {
+                        check_stmt(&parse_field_check(&field.attrs), quote! { #ident }, quote! { #i })
+                    })
+                    .collect();
+
+                quote! {
+                    Self::#variant_ident( #(#idents),* ) => { #(#stmts)* }
+                }
+            }
+            Fields::Unit => quote! { Self::#variant_ident => {} },
+        }
+    });
+
+    quote! {
+        let mut acc = ::openc2::Error::accumulator();
+        match self {
+            #(#arms)*
+        }
+        acc.finish()
+    }
+}
@@ -1,4 +1,4 @@
-use openc2::{DomainName, Ipv4Net, Ipv6Net, target::Device};
+use openc2::{DomainName, Error, ErrorAt, Ipv4Net, Ipv6Net, target::Device};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 
@@ -22,6 +22,13 @@ impl Args {
             && self.periodic_scan.is_none()
             && self.downstream_device.is_none()
     }
+
+    /// Returns the `downstream_device` argument, or an error if it's absent.
+    pub fn require_downstream_device(&self) -> Result<&DownstreamDevice, Error> {
+        self.downstream_device
+            .as_ref()
+            .ok_or_else(|| Error::validation("downstream_device is required").at("downstream_device"))
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
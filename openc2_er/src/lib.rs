@@ -1,8 +1,10 @@
 mod args;
+pub mod relay;
 pub mod target;
 
 pub use args::*;
 use openc2::Nsid;
+pub use relay::{DeviceDirectory, DownstreamRelay};
 pub use target::{Target, TargetType};
 
 pub const NS: &Nsid = &Nsid::ER;
@@ -0,0 +1,129 @@
+//! Fans a command out to the devices named by its `downstream_device` argument and merges their
+//! responses back into a single stream.
+
+use futures::{
+    StreamExt,
+    stream::{self, BoxStream},
+};
+use openc2::{
+    Error, ErrorAt, Message, Nsid,
+    json::{Command, Headers, Response},
+    target::Device,
+};
+use openc2_consumer::{Consume, util::stream_just};
+
+use crate::{Args, DownstreamDevice};
+
+/// Resolves the devices and per-device [`Consume`] transports a [`DownstreamRelay`] fans a
+/// command out to.
+pub trait DeviceDirectory {
+    /// The per-device transport this directory dispatches to.
+    type Transport: Consume + Send + Sync;
+
+    /// Expands `group` into the devices currently in it.
+    fn devices_in_group(&self, group: &str) -> Vec<Device>;
+
+    /// Expands `tenant_id` into every device belonging to that tenant.
+    fn devices_in_tenant(&self, tenant_id: &str) -> Vec<Device>;
+
+    /// Returns the transport used to relay a command to `device`, or `None` if this directory
+    /// doesn't know how to reach it.
+    fn transport(&self, device: &Device) -> Option<&Self::Transport>;
+}
+
+fn device_label(device: &Device) -> &str {
+    device
+        .device_id
+        .as_deref()
+        .or(device.hostname.as_deref())
+        .or(device.idn_hostname.as_deref())
+        .unwrap_or("<unknown device>")
+}
+
+fn tag(mut response: Response, label: &str) -> Response {
+    response.status_text = Some(match response.status_text.take() {
+        Some(text) => format!("[device {label}] {text}"),
+        None => format!("[device {label}]"),
+    });
+    response
+}
+
+/// A [`Consume`] that relays an ER command to every device, device group, and tenant named by
+/// its `downstream_device` argument, via a per-device transport looked up in `D`.
+///
+/// Each yielded [`Response`] is tagged with the device it came from. A device with no transport
+/// in the directory, or an error relaying to one, only fails that device's response; the rest of
+/// the fan-out continues.
+pub struct DownstreamRelay<D> {
+    directory: D,
+}
+
+impl<D: DeviceDirectory> DownstreamRelay<D> {
+    pub fn new(directory: D) -> Self {
+        Self { directory }
+    }
+
+    fn target_devices(&self, downstream: &DownstreamDevice) -> Vec<Device> {
+        let mut devices = downstream.devices.clone();
+        for group in &downstream.device_groups {
+            devices.extend(self.directory.devices_in_group(group));
+        }
+
+        if let Some(tenant_id) = &downstream.tenant_id {
+            devices.extend(self.directory.devices_in_tenant(tenant_id));
+        }
+
+        devices
+    }
+
+    fn relay_to<'a>(&'a self, device: Device, msg: Message<Headers, Command>) -> BoxStream<'a, Response> {
+        let label = device_label(&device).to_string();
+        let Some(transport) = self.directory.transport(&device) else {
+            return stream_just(tag(
+                Error::not_implemented(format!("no transport registered for device {label}"))
+                    .into(),
+                &label,
+            ));
+        };
+
+        transport
+            .consume(msg)
+            .map(move |response| tag(response, &label))
+            .boxed()
+    }
+}
+
+impl<D: DeviceDirectory + Send + Sync> Consume for DownstreamRelay<D> {
+    fn consume<'a>(&'a self, msg: Message<Headers, Command>) -> BoxStream<'a, Response> {
+        let downstream = msg
+            .body
+            .args
+            .extensions
+            .require::<Args>(&Nsid::ER)
+            .map_err(Error::validation)
+            .and_then(|args| args.require_downstream_device().cloned())
+            .at(Nsid::ER);
+
+        let downstream = match downstream {
+            Ok(downstream) => downstream,
+            Err(e) => return stream_just(e.into()),
+        };
+
+        let devices = self.target_devices(&downstream);
+        if devices.is_empty() {
+            return stream_just(
+                Error::validation("downstream_device did not resolve to any device")
+                    .at("downstream_device")
+                    .at(Nsid::ER)
+                    .into(),
+            );
+        }
+
+        stream::select_all(
+            devices
+                .into_iter()
+                .map(|device| self.relay_to(device, msg.clone())),
+        )
+        .boxed()
+    }
+}
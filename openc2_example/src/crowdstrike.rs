@@ -5,7 +5,8 @@ use futures::{
     stream::{self, BoxStream},
 };
 use openc2::{
-    Action, Error, ErrorAt, Hashes, Message, Nsid, Payload, Profile, StatusCode, TargetType,
+    Action, Error, ErrorAt, Hashes, Message, Nsid, Payload, Profile, Sha256Digest, StatusCode,
+    TargetType,
     json::{Command, Headers, Response, Target},
     target,
 };
@@ -405,10 +406,10 @@ impl Consume for Sandbox {
     }
 }
 
-fn require_sha256(hashes: &Hashes) -> Result<&str, Error> {
+fn require_sha256(hashes: &Hashes) -> Result<&Sha256Digest, Error> {
     hashes
         .sha256
-        .as_deref()
+        .as_ref()
         .ok_or_else(|| Error::validation("sha256 hash is required").at("hashes"))
 }
 
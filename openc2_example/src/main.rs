@@ -5,6 +5,7 @@ use futures::stream::StreamExt;
 use openc2::{
     Action, Args, Duration, Feature, Nsid, Period, ResponseType,
     json::Command,
+    negotiation::{self, Negotiated},
     target::{self, Device},
 };
 use openc2_consumer::{Consume, Registry};
@@ -72,6 +73,32 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             aid,
             duration,
         } => {
+            // Query the consumer's advertised features so we can pace commands to its rate
+            // limit instead of sending blind and getting throttled with `ServiceUnavailable`.
+            let features = registry
+                .consume(
+                    Command::new(
+                        Action::Query,
+                        vec![Feature::Pairs, Feature::Profiles, Feature::Versions],
+                    )
+                    .into(),
+                )
+                .next()
+                .await
+                .expect("stream yields at least one response");
+
+            let mut limiter = features
+                .results
+                .and_then(|results| {
+                    negotiation::negotiate(&results, &Default::default(), &Default::default()).ok()
+                })
+                .as_ref()
+                .and_then(Negotiated::rate_limiter);
+
+            if let Some(limiter) = limiter.as_mut() {
+                limiter.acquire().await;
+            }
+
             let rsp = registry.consume(
                 Command::new(
                     Action::Delete,
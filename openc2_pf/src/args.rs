@@ -1,11 +1,11 @@
 //! PF-specific command arguments for OpenC2
 
-use openc2::IsEmpty;
+use openc2::{Check, Error, IsEmpty};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 
 /// Specifies how to handle denied packets.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Check)]
 #[serde(rename_all = "snake_case")]
 pub enum DropProcess {
     /// Drop the packet and do not send a notification to the source of the packet.
@@ -17,7 +17,7 @@ pub enum DropProcess {
 }
 
 /// Specifies the direction for rule application.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Check)]
 #[serde(rename_all = "snake_case")]
 pub enum Direction {
     Both,
@@ -27,18 +27,35 @@ pub enum Direction {
 
 /// PF-specific arguments.
 #[skip_serializing_none]
-#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Check)]
 pub struct Args {
     pub drop_process: Option<DropProcess>,
+    #[check(skip)]
     pub persistent: Option<bool>,
     pub direction: Option<Direction>,
+    #[check(with = "check_insert_rule")]
     pub insert_rule: Option<u32>,
+    #[check(skip)]
     pub logged: Option<bool>,
+    #[check(skip)]
     pub description: Option<String>,
+    #[check(skip)]
     pub stateful: Option<bool>,
+    #[check(skip)]
     pub priority: Option<u32>,
 }
 
+/// PF rule numbers are 1-indexed, so `0` can never name an existing rule to insert before.
+fn check_insert_rule(insert_rule: &Option<u32>) -> Result<(), Error> {
+    if *insert_rule == Some(0) {
+        return Err(Error::validation(
+            "insert_rule must be greater than 0; PF rule numbers are 1-indexed",
+        ));
+    }
+
+    Ok(())
+}
+
 impl IsEmpty for Args {
     fn is_empty(&self) -> bool {
         self.drop_process.is_none()
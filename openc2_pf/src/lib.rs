@@ -2,10 +2,12 @@
 //! Implements types and logic for the PF actuator profile as defined in the OASIS specification.
 
 mod args;
+pub mod rules;
 pub mod target;
 
 pub use args::*;
 use openc2::Nsid;
+pub use rules::{Packet, ResolveNetTag, Rule, RuleTable};
 pub use target::*;
 
 pub static NS: &Nsid = &Nsid::PF;
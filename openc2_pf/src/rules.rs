@@ -0,0 +1,101 @@
+//! First-match evaluation of traffic against a table of installed PF rules.
+
+use std::{collections::BTreeMap, net::IpAddr};
+
+use openc2::{Action, Port, target::L4Protocol};
+
+use crate::target::{AdvAddr, AdvancedConnection, RuleId};
+
+/// A concrete 5-tuple to classify against a [`RuleTable`].
+#[derive(Debug, Clone, Copy)]
+pub struct Packet {
+    pub src_addr: IpAddr,
+    pub src_port: Port,
+    pub dst_addr: IpAddr,
+    pub dst_port: Port,
+    pub protocol: L4Protocol,
+}
+
+/// Resolves a [`AdvAddr::NetTag`] to the concrete CIDR blocks it currently refers to.
+pub trait ResolveNetTag {
+    fn resolve(&self, tag: &str) -> Vec<AdvAddr>;
+}
+
+fn matches_addr(spec: &AdvAddr, addr: IpAddr, resolver: &impl ResolveNetTag) -> bool {
+    match spec {
+        AdvAddr::V4Addr(net) => matches!(addr, IpAddr::V4(v4) if net.contains(v4)),
+        AdvAddr::V6Addr(net) => matches!(addr, IpAddr::V6(v6) if net.contains(v6)),
+        AdvAddr::NetTag(tag) => resolver
+            .resolve(tag)
+            .iter()
+            .any(|resolved| matches_addr(resolved, addr, resolver)),
+    }
+}
+
+fn matches_port(spec: Option<Port>, port: Port) -> bool {
+    spec.is_none_or(|expected| expected == port)
+}
+
+fn matches_protocol(spec: Option<L4Protocol>, protocol: L4Protocol) -> bool {
+    spec.is_none_or(|expected| expected == protocol)
+}
+
+impl AdvancedConnection {
+    /// Returns `true` if `packet` satisfies this connection's match spec. A field left unset
+    /// acts as a wildcard; an [`AdvAddr::NetTag`] is resolved through `resolver` before testing
+    /// containment. A family mismatch between an address field and `packet` counts as no match.
+    pub fn matches(&self, packet: &Packet, resolver: &impl ResolveNetTag) -> bool {
+        matches_addr(&self.src_addr, packet.src_addr, resolver)
+            && matches_addr(&self.dst_addr, packet.dst_addr, resolver)
+            && matches_port(self.src_port, packet.src_port)
+            && matches_port(self.dst_port, packet.dst_port)
+            && matches_protocol(self.protocol, packet.protocol)
+    }
+}
+
+/// A single installed packet-filtering rule.
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub spec: AdvancedConnection,
+    pub action: Action,
+}
+
+/// A table of installed PF rules, keyed by their immutable [`RuleId`].
+///
+/// [`RuleTable::classify`] evaluates a packet against the table in ascending `RuleId` order and
+/// returns the action of the first matching rule (first-match-wins).
+#[derive(Debug, Clone, Default)]
+pub struct RuleTable {
+    rules: BTreeMap<RuleId, Rule>,
+}
+
+impl RuleTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Installs or replaces the rule at `id`.
+    pub fn insert(&mut self, id: RuleId, spec: AdvancedConnection, action: Action) {
+        self.rules.insert(id, Rule { spec, action });
+    }
+
+    /// Removes the rule at `id`, if one is installed.
+    pub fn remove(&mut self, id: RuleId) -> Option<Rule> {
+        self.rules.remove(&id)
+    }
+
+    /// Classifies `packet` against the installed rules in ascending `RuleId` order, returning
+    /// the action of the first matching rule, or `default_action` if none match.
+    pub fn classify(
+        &self,
+        packet: &Packet,
+        resolver: &impl ResolveNetTag,
+        default_action: Action,
+    ) -> Action {
+        self.rules
+            .values()
+            .find(|rule| rule.spec.matches(packet, resolver))
+            .map(|rule| rule.action.clone())
+            .unwrap_or(default_action)
+    }
+}
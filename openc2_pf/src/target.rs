@@ -164,6 +164,41 @@ impl From<Ipv6Addr> for AdvAddr {
     }
 }
 
+impl AdvAddr {
+    /// Returns `true` if this address falls within a well-known private, loopback, or
+    /// link-local range: RFC 1918 for IPv4, and unique-local/loopback for IPv6.
+    ///
+    /// A [`AdvAddr::NetTag`] can't be classified without resolving it first, so this always
+    /// returns `false` for one.
+    pub fn is_private(&self) -> bool {
+        match self {
+            AdvAddr::V4Addr(net) => {
+                let addr = net.address();
+                addr.is_private() || addr.is_loopback() || addr.is_link_local()
+            }
+            AdvAddr::V6Addr(net) => {
+                let addr = net.address();
+                addr.is_loopback()
+                    || (addr.segments()[0] & 0xfe00) == 0xfc00
+                    || (addr.segments()[0] & 0xffc0) == 0xfe80
+            }
+            AdvAddr::NetTag(_) => false,
+        }
+    }
+
+    /// Returns `true` if this address is known to fall outside any private, loopback, or
+    /// link-local range.
+    ///
+    /// A [`AdvAddr::NetTag`] can't be classified without resolving it first, so this always
+    /// returns `false` for one.
+    pub fn is_public(&self) -> bool {
+        match self {
+            AdvAddr::NetTag(_) => false,
+            _ => !self.is_private(),
+        }
+    }
+}
+
 impl PartialEq<Ipv4Net> for AdvAddr {
     fn eq(&self, other: &Ipv4Net) -> bool {
         matches!(self, AdvAddr::V4Addr(v4) if v4 == other)